@@ -0,0 +1,43 @@
+// Benchmarks for building styled output.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use flower_pot::*;
+
+fn styles() -> [Style; 4] {
+    [
+        Style::new().bold(),
+        Style::new().fg(Color::Named(NamedColor::Red)),
+        Style::new().italic().underline().fg(Color::Palette(214)),
+        Style::new().bold().dim().strikethrough().fg(Color::Rgb(255, 45, 68)).bg(Color::Rgb(12, 34, 56)),
+    ]
+}
+
+fn display(c: &mut Criterion) {
+    let styles = styles();
+    c.bench_function("style_to_string", |b| {
+        b.iter(|| {
+            for style in &styles {
+                black_box(black_box(style).to_string());
+            }
+        })
+    });
+}
+
+fn styled_line(c: &mut Criterion) {
+    let styles = styles();
+    c.bench_function("styled_line", |b| {
+        let mut line = String::with_capacity(256);
+        b.iter(|| {
+            use core::fmt::Write;
+
+            line.clear();
+            for (i, style) in styles.iter().enumerate() {
+                let _ = write!(line, "{} ", Styled::new(i, black_box(style)));
+            }
+            black_box(&line);
+        })
+    });
+}
+
+criterion_group!(benches, display, styled_line);
+criterion_main!(benches);
@@ -0,0 +1,123 @@
+// Non-allocating versions of the color functions.
+
+use core::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum CodeColor {
+    Palette(u8),
+    Rgb(u8, u8, u8),
+}
+
+/// A 256-color or truecolor control code that writes itself directly to a formatter when
+/// displayed, so it can be used with `print!`, `write!`, and friends without allocating a String.
+/// Returned by `color_256_code`, `color_256_bg_code`, `truecolor_code`, and `truecolor_bg_code`.
+/// The displayed output is exactly the same as the String returned by the corresponding function
+/// without the `_code` suffix.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ColorCode {
+    color: CodeColor,
+    background: bool,
+}
+
+impl fmt::Display for ColorCode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let layer = if self.background { 48 } else { 38 };
+        match self.color {
+            CodeColor::Palette(n)     => write!(f, "\x1b[{layer};5;{n}m"),
+            CodeColor::Rgb(r, g, b)   => write!(f, "\x1b[{layer};2;{r};{g};{b}m"),
+        }
+    }
+}
+
+/// Same as `color_256`, but returns a `ColorCode` instead of a String.
+pub const fn color_256_code(n: u8) -> ColorCode {
+    ColorCode { color: CodeColor::Palette(n), background: false }
+}
+
+/// Same as `color_256_bg`, but returns a `ColorCode` instead of a String.
+pub const fn color_256_bg_code(n: u8) -> ColorCode {
+    ColorCode { color: CodeColor::Palette(n), background: true }
+}
+
+/// Same as `truecolor`, but returns a `ColorCode` instead of a String.
+pub const fn truecolor_code(r: u8, g: u8, b: u8) -> ColorCode {
+    ColorCode { color: CodeColor::Rgb(r, g, b), background: false }
+}
+
+/// Same as `truecolor_bg`, but returns a `ColorCode` instead of a String.
+pub const fn truecolor_bg_code(r: u8, g: u8, b: u8) -> ColorCode {
+    ColorCode { color: CodeColor::Rgb(r, g, b), background: true }
+}
+
+/// Write the same control code as `color_256` into `w`, without allocating. Useful for building up
+/// a styled line in a single reusable String.
+pub fn color_256_into(w: &mut impl fmt::Write, n: u8) -> fmt::Result {
+    write!(w, "{}", color_256_code(n))
+}
+
+/// Write the same control code as `color_256_bg` into `w`, without allocating.
+pub fn color_256_bg_into(w: &mut impl fmt::Write, n: u8) -> fmt::Result {
+    write!(w, "{}", color_256_bg_code(n))
+}
+
+/// Write the same control code as `truecolor` into `w`, without allocating. Useful for building up
+/// a styled line in a single reusable String.
+///
+/// ```
+/// use flower_pot::*;
+///
+/// let mut line = String::new();
+/// for (i, c) in "hi!".chars().enumerate() {
+///     truecolor_into(&mut line, 0, 80 * i as u8, 255).unwrap();
+///     line.push(c);
+/// }
+/// line.push_str(RESET);
+/// ```
+pub fn truecolor_into(w: &mut impl fmt::Write, r: u8, g: u8, b: u8) -> fmt::Result {
+    write!(w, "{}", truecolor_code(r, g, b))
+}
+
+/// Write the same control code as `truecolor_bg` into `w`, without allocating.
+pub fn truecolor_bg_into(w: &mut impl fmt::Write, r: u8, g: u8, b: u8) -> fmt::Result {
+    write!(w, "{}", truecolor_bg_code(r, g, b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "alloc")]
+    use crate::{color_256, color_256_bg, truecolor, truecolor_bg};
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn codes_match_strings() {
+        for n in 0..=255 {
+            assert_eq!(format!("{}", color_256_code(n)),       color_256(n));
+            assert_eq!(format!("{}", color_256_bg_code(n)),    color_256_bg(n));
+        }
+
+        for (r, g, b) in [(0, 0, 0), (127, 45, 68), (255, 255, 255), (1, 20, 200)] {
+            assert_eq!(format!("{}", truecolor_code(r, g, b)),     truecolor(r, g, b));
+            assert_eq!(format!("{}", truecolor_bg_code(r, g, b)),  truecolor_bg(r, g, b));
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn into_matches_strings() {
+        let mut out = String::new();
+        color_256_into(&mut out, 214).unwrap();
+        color_256_bg_into(&mut out, 7).unwrap();
+        truecolor_into(&mut out, 127, 45, 68).unwrap();
+        truecolor_bg_into(&mut out, 0, 255, 255).unwrap();
+
+        let expected = [color_256(214), color_256_bg(7), truecolor(127, 45, 68), truecolor_bg(0, 255, 255)].concat();
+        assert_eq!(out, expected);
+    }
+
+    #[test]
+    fn codes_in_format_strings() {
+        let code = truecolor_code(1, 2, 3);
+        assert_eq!(format!("{code}text{code}"), "\x1b[38;2;1;2;3mtext\x1b[38;2;1;2;3m");
+    }
+}
@@ -0,0 +1,747 @@
+// Colors as values.
+
+use core::fmt;
+
+#[cfg(feature = "alloc")]
+use alloc::borrow::Cow;
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+
+use crate::*;
+#[cfg(feature = "alloc")]
+use crate::config::write_gated;
+
+/// The control codes that set the 16 named colors as the foreground color, indexed by their
+/// number: 0–7 are `BLACK` through `WHITE`, and 8–15 are `BRIGHT_BLACK` through `BRIGHT_WHITE`
+/// (the same order as `NamedColor::all()` and the first 16 entries of the 256-color palette).
+///
+/// ```
+/// use flower_pot::*;
+///
+/// assert_eq!(NAMED_FG[1], RED);
+/// assert_eq!(NAMED_FG[9], BRIGHT_RED);
+/// ```
+pub const NAMED_FG: [&'static str; 16] = [
+    BLACK,          RED,            GREEN,          YELLOW,
+    BLUE,           MAGENTA,        CYAN,           WHITE,
+    BRIGHT_BLACK,   BRIGHT_RED,     BRIGHT_GREEN,   BRIGHT_YELLOW,
+    BRIGHT_BLUE,    BRIGHT_MAGENTA, BRIGHT_CYAN,    BRIGHT_WHITE,
+];
+
+/// The control codes that set the 16 named colors as the background color, indexed by their
+/// number: 0–7 are `BLACK_BG` through `WHITE_BG`, and 8–15 are `BRIGHT_BLACK_BG` through
+/// `BRIGHT_WHITE_BG` (see `NAMED_FG`).
+pub const NAMED_BG: [&'static str; 16] = [
+    BLACK_BG,           RED_BG,             GREEN_BG,           YELLOW_BG,
+    BLUE_BG,            MAGENTA_BG,         CYAN_BG,            WHITE_BG,
+    BRIGHT_BLACK_BG,    BRIGHT_RED_BG,      BRIGHT_GREEN_BG,    BRIGHT_YELLOW_BG,
+    BRIGHT_BLUE_BG,     BRIGHT_MAGENTA_BG,  BRIGHT_CYAN_BG,     BRIGHT_WHITE_BG,
+];
+
+const NAMES: [&str; 16] = [
+    "black",            "red",              "green",            "yellow",
+    "blue",             "magenta",          "cyan",             "white",
+    "bright black",     "bright red",       "bright green",     "bright yellow",
+    "bright blue",      "bright magenta",   "bright cyan",      "bright white",
+];
+
+/// The control code that sets the named color number `n` as the foreground color (see `NAMED_FG`).
+/// Numbers above 15 are treated as 15, so they give `BRIGHT_WHITE`.
+///
+/// ```
+/// use flower_pot::*;
+///
+/// assert_eq!(named_fg(3), YELLOW);
+/// assert_eq!(named_fg(200), BRIGHT_WHITE);
+/// ```
+pub const fn named_fg(n: u8) -> &'static str {
+    NAMED_FG[if n < 16 { n as usize } else { 15 }]
+}
+
+/// The control code that sets the named color number `n` as the background color (see
+/// `NAMED_BG`). Numbers above 15 are treated as 15, so they give `BRIGHT_WHITE_BG`.
+pub const fn named_bg(n: u8) -> &'static str {
+    NAMED_BG[if n < 16 { n as usize } else { 15 }]
+}
+
+/// One of the 16 named colors: the 8 standard colors and their bright versions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum NamedColor {
+    /// Black (`BLACK`, `BLACK_BG`).
+    Black,
+    /// Red (`RED`, `RED_BG`).
+    Red,
+    /// Green (`GREEN`, `GREEN_BG`).
+    Green,
+    /// Yellow (`YELLOW`, `YELLOW_BG`).
+    Yellow,
+    /// Blue (`BLUE`, `BLUE_BG`).
+    Blue,
+    /// Magenta (`MAGENTA`, `MAGENTA_BG`).
+    Magenta,
+    /// Cyan (`CYAN`, `CYAN_BG`).
+    Cyan,
+    /// White (`WHITE`, `WHITE_BG`).
+    White,
+    /// Bright black (`BRIGHT_BLACK`, `BRIGHT_BLACK_BG`).
+    BrightBlack,
+    /// Bright red (`BRIGHT_RED`, `BRIGHT_RED_BG`).
+    BrightRed,
+    /// Bright green (`BRIGHT_GREEN`, `BRIGHT_GREEN_BG`).
+    BrightGreen,
+    /// Bright yellow (`BRIGHT_YELLOW`, `BRIGHT_YELLOW_BG`).
+    BrightYellow,
+    /// Bright blue (`BRIGHT_BLUE`, `BRIGHT_BLUE_BG`).
+    BrightBlue,
+    /// Bright magenta (`BRIGHT_MAGENTA`, `BRIGHT_MAGENTA_BG`).
+    BrightMagenta,
+    /// Bright cyan (`BRIGHT_CYAN`, `BRIGHT_CYAN_BG`).
+    BrightCyan,
+    /// Bright white (`BRIGHT_WHITE`, `BRIGHT_WHITE_BG`).
+    BrightWhite,
+}
+
+impl NamedColor {
+    const ALL: [NamedColor; 16] = [
+        NamedColor::Black,          NamedColor::Red,            NamedColor::Green,          NamedColor::Yellow,
+        NamedColor::Blue,           NamedColor::Magenta,        NamedColor::Cyan,           NamedColor::White,
+        NamedColor::BrightBlack,    NamedColor::BrightRed,      NamedColor::BrightGreen,    NamedColor::BrightYellow,
+        NamedColor::BrightBlue,     NamedColor::BrightMagenta,  NamedColor::BrightCyan,     NamedColor::BrightWhite,
+    ];
+
+    /// All 16 named colors: the 8 standard colors from black to white, followed by their bright
+    /// versions in the same order.
+    ///
+    /// ```
+    /// use flower_pot::*;
+    ///
+    /// for color in NamedColor::all() {
+    ///     println!("{}{}{RESET}", color.fg(), color.name());
+    /// }
+    /// ```
+    pub const fn all() -> &'static [NamedColor] {
+        &Self::ALL
+    }
+
+    /// The control code that sets this color as the foreground color, such as `RED` for
+    /// `NamedColor::Red`.
+    pub const fn fg(self) -> &'static str {
+        NAMED_FG[self as usize]
+    }
+
+    /// The control code that sets this color as the background color, such as `RED_BG` for
+    /// `NamedColor::Red`.
+    pub const fn bg(self) -> &'static str {
+        NAMED_BG[self as usize]
+    }
+
+    /// A human-readable, lowercase name for this color, such as `"red"` or `"bright blue"`.
+    pub const fn name(self) -> &'static str {
+        NAMES[self as usize]
+    }
+
+    /// Find the named color with the given name (see `name`), ignoring ASCII case and accepting `_`
+    /// or `-` in place of the space in the bright colors' names, so `"bright black"`,
+    /// `"Bright_Black"`, and `"bright-black"` all work.
+    pub(crate) fn from_name(name: &str) -> Option<NamedColor> {
+        Self::ALL.into_iter().find(|color| {
+            let expected = color.name().as_bytes();
+            name.len() == expected.len()
+                && name.bytes().zip(expected).all(|(a, &b)| {
+                    a.eq_ignore_ascii_case(&b) || (b == b' ' && (a == b'_' || a == b'-'))
+                })
+        })
+    }
+
+    /// The approximate RGB value of this color. Terminals are free to choose their own values for
+    /// the named colors (and many let the user configure them), so this uses xterm's defaults:
+    ///
+    /// | Color     | Standard        | Bright          |
+    /// |-----------|-----------------|-----------------|
+    /// | black     | (0, 0, 0)       | (127, 127, 127) |
+    /// | red       | (205, 0, 0)     | (255, 0, 0)     |
+    /// | green     | (0, 205, 0)     | (0, 255, 0)     |
+    /// | yellow    | (205, 205, 0)   | (255, 255, 0)   |
+    /// | blue      | (0, 0, 238)     | (92, 92, 255)   |
+    /// | magenta   | (205, 0, 205)   | (255, 0, 255)   |
+    /// | cyan      | (0, 205, 205)   | (0, 255, 255)   |
+    /// | white     | (229, 229, 229) | (255, 255, 255) |
+    ///
+    /// These are also the values `palette_to_rgb` gives for palette indices 0–15.
+    pub const fn to_rgb(self) -> (u8, u8, u8) {
+        palette_to_rgb(self.index())
+    }
+
+    /// The color at position `index` in the list above, if `index` is less than 16.
+    pub(crate) const fn from_index(index: u8) -> Option<NamedColor> {
+        if index < 16 { Some(Self::ALL[index as usize]) } else { None }
+    }
+
+    /// The position of this color in the list above (0 for black through 15 for bright white),
+    /// which is also its index in the 256-color palette.
+    pub(crate) const fn index(self) -> u8 {
+        self as u8
+    }
+
+    /// The SGR parameter that sets this color as the foreground color (30–37 or 90–97).
+    pub(crate) const fn fg_param(self) -> u8 {
+        match self.index() {
+            i @ 0..8    => 30 + i,
+            i           => 90 + (i - 8),
+        }
+    }
+
+    /// The SGR parameter that sets this color as the background color (40–47 or 100–107).
+    pub(crate) const fn bg_param(self) -> u8 {
+        self.fg_param() + 10
+    }
+}
+
+/// The control code that sets `color` as the background color, taking into account whether the
+/// terminal understands the bright background codes (see `set_legacy_bright`). Normally this is
+/// the same as `color.bg()`. On a legacy terminal, which would show no background at all for the
+/// bright background codes (100–107), each bright color falls back to the standard background
+/// color of the same hue:
+///
+/// | Color          | Normally             | On a legacy terminal |
+/// |----------------|----------------------|----------------------|
+/// | bright black   | `BRIGHT_BLACK_BG`    | `BLACK_BG`           |
+/// | bright red     | `BRIGHT_RED_BG`      | `RED_BG`             |
+/// | bright green   | `BRIGHT_GREEN_BG`    | `GREEN_BG`           |
+/// | bright yellow  | `BRIGHT_YELLOW_BG`   | `YELLOW_BG`          |
+/// | bright blue    | `BRIGHT_BLUE_BG`     | `BLUE_BG`            |
+/// | bright magenta | `BRIGHT_MAGENTA_BG`  | `MAGENTA_BG`         |
+/// | bright cyan    | `BRIGHT_CYAN_BG`     | `CYAN_BG`            |
+/// | bright white   | `BRIGHT_WHITE_BG`    | `WHITE_BG`           |
+///
+/// The 8 standard colors always use their own background codes (40–47).
+///
+/// ```
+/// use flower_pot::*;
+///
+/// assert_eq!(bright_bg_adaptive(NamedColor::BrightRed), BRIGHT_RED_BG);
+///
+/// set_legacy_bright(true);
+/// assert_eq!(bright_bg_adaptive(NamedColor::BrightRed), RED_BG);
+/// assert_eq!(bright_bg_adaptive(NamedColor::Blue), BLUE_BG);
+/// # set_legacy_bright(false);
+/// ```
+pub fn bright_bg_adaptive(color: NamedColor) -> &'static str {
+    if legacy_bright() {
+        NAMED_BG[color.index() as usize % 8]
+    } else {
+        color.bg()
+    }
+}
+
+/// The index in the 256-color palette of `color`, for terminals that support the palette but not
+/// the bright named color codes (90–97 and 100–107). The palette starts with the 16 named colors,
+/// so the standard colors map to indices 0–7 and the bright colors to indices 8–15:
+///
+/// | Color          | Index | Color          | Index |
+/// |----------------|-------|----------------|-------|
+/// | black          | 0     | bright black   | 8     |
+/// | red            | 1     | bright red     | 9     |
+/// | green          | 2     | bright green   | 10    |
+/// | yellow         | 3     | bright yellow  | 11    |
+/// | blue           | 4     | bright blue    | 12    |
+/// | magenta        | 5     | bright magenta | 13    |
+/// | cyan           | 6     | bright cyan    | 14    |
+/// | white          | 7     | bright white   | 15    |
+///
+/// ```
+/// use flower_pot::*;
+///
+/// assert_eq!(bright_fallback(NamedColor::BrightRed), 9);
+/// assert_eq!(color_256_str(bright_fallback(NamedColor::BrightRed)), "\x1b[38;5;9m");
+/// ```
+pub const fn bright_fallback(color: NamedColor) -> u8 {
+    color.index()
+}
+
+/// The control code that sets `color` as the foreground color, taking into account whether the
+/// terminal understands the bright color codes (see `set_legacy_bright`). Normally this is the
+/// same as `color.fg()`. On a legacy terminal, the bright colors use their entry in the 256-color
+/// palette instead (see `bright_fallback`), which keeps their brightness on terminals that support
+/// the palette. The 8 standard colors always use their own codes (30–37).
+///
+/// ```
+/// use flower_pot::*;
+///
+/// // BRIGHT_YELLOW normally, or the palette color 11 on a legacy terminal.
+/// println!("{}warning{RESET}", bright_fg_adaptive(NamedColor::BrightYellow));
+/// ```
+pub fn bright_fg_adaptive(color: NamedColor) -> &'static str {
+    if legacy_bright() && color.index() >= 8 {
+        color_256_str(bright_fallback(color))
+    } else {
+        color.fg()
+    }
+}
+
+/// Like `bright_bg_adaptive`, but on a legacy terminal, the bright colors use their entry in the
+/// 256-color palette as the background color (see `bright_fallback`) instead of falling back to
+/// the standard color of the same hue. Use this one if the terminal is known to support the
+/// palette (see `supports_256_color`).
+///
+/// ```
+/// use flower_pot::*;
+///
+/// // BRIGHT_BLUE_BG normally, or the palette color 12 on a legacy terminal.
+/// println!("{}selected{RESET}", bright_bg_256_adaptive(NamedColor::BrightBlue));
+/// ```
+pub fn bright_bg_256_adaptive(color: NamedColor) -> &'static str {
+    if legacy_bright() && color.index() >= 8 {
+        color_256_bg_str(bright_fallback(color))
+    } else {
+        color.bg()
+    }
+}
+
+/// A color that can be used as a foreground or background color: one of the 16 named colors, an
+/// entry of the 256-color palette, or an RGB value for truecolor terminals.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Color {
+    /// A named color, like the one set by `RED` or `BRIGHT_BLUE_BG`.
+    Named(NamedColor),
+    /// An entry of the 256-color palette, like the one set by `color_256`.
+    Palette(u8),
+    /// An RGB value, like the one set by `truecolor`.
+    Rgb(u8, u8, u8),
+}
+
+impl Color {
+    /// A style with this color as the foreground color and `bg` as the background color, for the
+    /// common case of colored text on a colored background. It displays as a single control code
+    /// that sets both, without allocating.
+    ///
+    /// ```
+    /// use flower_pot::*;
+    ///
+    /// let warning = Color::Rgb(255, 200, 0).on(Color::Named(NamedColor::Blue));
+    /// assert_eq!(warning.to_string(), "\x1b[38;2;255;200;0;44m");
+    ///
+    /// println!("{}", "careful!".styled(warning));
+    /// ```
+    pub const fn on(self, bg: Color) -> Style {
+        Style::new().fg(self).bg(bg)
+    }
+
+    /// The RGB value of this color. Named colors and palette entries are converted with
+    /// `palette_to_rgb`, so the result is only an approximation of what a given terminal shows.
+    pub const fn to_rgb(self) -> (u8, u8, u8) {
+        match self {
+            Color::Named(c)     => c.to_rgb(),
+            Color::Palette(n)   => palette_to_rgb(n),
+            Color::Rgb(r, g, b) => (r, g, b),
+        }
+    }
+
+    /// The color of the same kind as this one (named, palette, or RGB) that is closest to `rgb`.
+    fn same_kind(self, (r, g, b): (u8, u8, u8)) -> Color {
+        match self {
+            Color::Named(_)     => Color::Named(rgb_to_named_bright(r, g, b)),
+            Color::Palette(_)   => Color::Palette(rgb_to_256(r, g, b)),
+            Color::Rgb(..)      => Color::Rgb(r, g, b),
+        }
+    }
+
+    /// The opposite of this color: each channel of its RGB value (see `Color::to_rgb`) is replaced
+    /// by 255 minus that channel, so black becomes white and red becomes cyan. The result is of
+    /// the same kind as this color, so named colors and palette entries give the nearest named
+    /// color or palette entry to the exact opposite.
+    ///
+    /// ```
+    /// use flower_pot::*;
+    ///
+    /// assert_eq!(Color::Rgb(255, 200, 0).invert(), Color::Rgb(0, 55, 255));
+    /// assert_eq!(Color::Named(NamedColor::Black).invert(), Color::Named(NamedColor::BrightWhite));
+    /// ```
+    pub fn invert(self) -> Color {
+        let (r, g, b) = self.to_rgb();
+        self.same_kind((255 - r, 255 - g, 255 - b))
+    }
+
+    /// Black or white, whichever is more readable on top of this color (see `readable_fg`), as a
+    /// color of the same kind as this one: `Black` or `BrightWhite` for named colors, 16 or 231 for
+    /// palette entries, and (0, 0, 0) or (255, 255, 255) for RGB values. Use it to pick a text
+    /// color for a background, or the other way around.
+    ///
+    /// ```
+    /// use flower_pot::*;
+    ///
+    /// let bg = Color::Palette(226); // bright yellow
+    /// assert_eq!(bg.contrasting(), Color::Palette(16));
+    /// println!("{}", "highlighted".styled(bg.contrasting().on(bg)));
+    /// ```
+    pub fn contrasting(self) -> Color {
+        self.same_kind(readable_fg(self.to_rgb()))
+    }
+
+    /// Write the SGR parameters that set this color (without the surrounding `\x1b[` and `m`).
+    pub(crate) fn write_params(&self, f: &mut impl fmt::Write, background: bool) -> fmt::Result {
+        let layer = if background { 48 } else { 38 };
+        match *self {
+            Color::Named(c) if background   => write!(f, "{}", c.bg_param()),
+            Color::Named(c)                 => write!(f, "{}", c.fg_param()),
+            Color::Palette(n)               => write!(f, "{layer};5;{n}"),
+            Color::Rgb(r, g, b)             => write!(f, "{layer};2;{r};{g};{b}"),
+        }
+    }
+
+    /// Write the SGR parameters that set this color as the underline color.
+    pub(crate) fn write_underline_params(&self, f: &mut impl fmt::Write) -> fmt::Result {
+        match *self {
+            Color::Named(c)     => write!(f, "58;5;{}", c.index()),
+            Color::Palette(n)   => write!(f, "58;5;{n}"),
+            Color::Rgb(r, g, b) => write!(f, "58;2;{r};{g};{b}"),
+        }
+    }
+}
+
+/// The control code that sets the foreground color to `color`, such as `\x1b[33m` for
+/// `Color::Named(NamedColor::Yellow)`. Like `styled`, this honors the global switches: it returns
+/// the empty string while colors are turned off (see `set_colors_enabled`).
+///
+/// ```
+/// use flower_pot::*;
+///
+/// println!("{}warning{RESET}", fg(Color::Named(NamedColor::Yellow)));
+/// assert_eq!(fg(Color::Rgb(1, 2, 3)), truecolor(1, 2, 3));
+/// ```
+#[cfg(feature = "alloc")]
+pub fn fg(color: Color) -> String {
+    let mut out = String::new();
+    let _ = write_gated(&mut out, Style::new().fg(color));
+    out
+}
+
+/// The control code that sets the background color to `color`, such as `\x1b[44m` for
+/// `Color::Named(NamedColor::Blue)`. Like `fg`, this returns the empty string while colors are
+/// turned off.
+#[cfg(feature = "alloc")]
+pub fn bg(color: Color) -> String {
+    let mut out = String::new();
+    let _ = write_gated(&mut out, Style::new().bg(color));
+    out
+}
+
+/// The control code that sets the foreground color to `color`, as a `Cow`: borrowed for named and
+/// palette colors, whose codes are all constants (see `NAMED_FG` and `color_256_str`), and owned
+/// for RGB colors, whose codes have to be formatted. So a list of codes can mix constants and
+/// computed codes under one type, such as `Vec<Cow<'static, str>>`, and only the RGB colors
+/// allocate. Unlike `fg`, this ignores the global switches, like the constants do.
+///
+/// ```
+/// use std::borrow::Cow;
+/// use flower_pot::*;
+///
+/// let codes: Vec<Cow<'static, str>> = vec![
+///     Cow::Borrowed(BOLD),
+///     fg_code(Color::Named(NamedColor::Red)),
+///     bg_code(Color::Rgb(0, 0, 64)),
+/// ];
+/// assert!(matches!(codes[1], Cow::Borrowed(RED)));
+/// assert_eq!(codes.concat(), format!("{BOLD}{RED}{}", truecolor_bg(0, 0, 64)));
+/// ```
+#[cfg(feature = "alloc")]
+pub fn fg_code(color: Color) -> Cow<'static, str> {
+    match color {
+        Color::Named(c)     => Cow::Borrowed(c.fg()),
+        Color::Palette(n)   => Cow::Borrowed(color_256_str(n)),
+        Color::Rgb(r, g, b) => Cow::Owned(truecolor(r, g, b)),
+    }
+}
+
+/// The control code that sets the background color to `color`, as a `Cow`. See `fg_code`.
+#[cfg(feature = "alloc")]
+pub fn bg_code(color: Color) -> Cow<'static, str> {
+    match color {
+        Color::Named(c)     => Cow::Borrowed(c.bg()),
+        Color::Palette(n)   => Cow::Borrowed(color_256_bg_str(n)),
+        Color::Rgb(r, g, b) => Cow::Owned(truecolor_bg(r, g, b)),
+    }
+}
+
+/// Like `fg`, but if `caps` is given, sets the closest color to `color` that a terminal with those
+/// capabilities can display instead (see `Capabilities::color`), or returns the empty string if it
+/// can't display colors at all. The global switches (see `set_colors_enabled`) still apply.
+///
+/// ```
+/// use flower_pot::*;
+///
+/// let caps = Capabilities { truecolor: false, ..Capabilities::ALL };
+/// assert_eq!(fg_with(Color::Rgb(255, 0, 0), Some(&caps)), color_256(196));
+/// assert_eq!(fg_with(Color::Rgb(255, 0, 0), Some(&Capabilities::NONE)), "");
+/// assert_eq!(fg_with(Color::Rgb(255, 0, 0), None), truecolor(255, 0, 0));
+/// ```
+#[cfg(feature = "alloc")]
+pub fn fg_with(color: Color, caps: Option<&Capabilities>) -> String {
+    match caps {
+        Some(caps)  => caps.color(color).map_or_else(String::new, fg),
+        None        => fg(color),
+    }
+}
+
+/// Like `bg`, but if `caps` is given, sets the closest color to `color` that a terminal with those
+/// capabilities can display instead. See `fg_with`.
+#[cfg(feature = "alloc")]
+pub fn bg_with(color: Color, caps: Option<&Capabilities>) -> String {
+    match caps {
+        Some(caps)  => caps.color(color).map_or_else(String::new, bg),
+        None        => bg(color),
+    }
+}
+
+/// How far `dim_fg` darkens a color towards black when it can't use `DIM` (see `darken`).
+const DIM_DARKEN: f32 = 0.4;
+
+/// The control code for muted text in the color `color`: `DIM` together with `color` as the
+/// foreground color while text attributes are on (see `set_attributes_enabled`), since that lets
+/// the terminal decide how to dim the color. Some terminals ignore `DIM`, so while attributes are
+/// off, this sets a darker version of the color instead: `color` darkened 40% of the way towards
+/// black with `darken`, approximated by the same kind of color as `color` (a palette color
+/// becomes the nearest palette color, and a named color becomes the nearest named color, which is
+/// often the color itself; use `dim_fg_with` to allow a palette or RGB color instead). Like `fg`,
+/// this leaves out the color while colors are off.
+///
+/// ```
+/// use flower_pot::*;
+///
+/// println!("{}(3 files skipped){RESET}", dim_fg(Color::Named(NamedColor::White)));
+/// ```
+#[cfg(feature = "alloc")]
+pub fn dim_fg(color: Color) -> String {
+    dim_fg_with(color, None)
+}
+
+/// Like `dim_fg`, but if `caps` is given, only uses `DIM` if the terminal supports text attributes
+/// (`caps.attributes_ok`), and otherwise sets the closest color to the darkened color that the
+/// terminal can display (see `Capabilities::color`).
+///
+/// ```
+/// use flower_pot::*;
+///
+/// let caps = Capabilities { attributes_ok: false, ..Capabilities::ALL };
+/// assert_eq!(dim_fg_with(Color::Rgb(200, 100, 0), Some(&caps)), truecolor(120, 60, 0));
+/// ```
+#[cfg(feature = "alloc")]
+pub fn dim_fg_with(color: Color, caps: Option<&Capabilities>) -> String {
+    if attributes_enabled() && caps.is_none_or(|caps| caps.attributes_ok) {
+        let style = match caps.map_or(Some(color), |caps| caps.color(color)) {
+            Some(color) => Style::new().dim().fg(color),
+            None        => Style::new().dim(),
+        };
+        let mut out = String::new();
+        let _ = write_gated(&mut out, style);
+        return out;
+    }
+
+    let (r, g, b) = darken(color.to_rgb(), DIM_DARKEN);
+    let dark = if caps.is_some() { Color::Rgb(r, g, b) } else { color.same_kind((r, g, b)) };
+    fg_with(dark, caps)
+}
+
+/// Choose between two colors depending on the terminal's background (see `set_background`): `dark`
+/// if the background is dark, which is the default, and `light` if it is light. This lets the
+/// same code stay readable on both kinds of terminal themes, where, for example, white text would
+/// be invisible on a light background. The choice is made when this is called, so call it when
+/// the color is used rather than storing the result.
+///
+/// ```
+/// use flower_pot::*;
+/// # #[cfg(feature = "alloc")] {
+///
+/// let text = adaptive(Color::Named(NamedColor::BrightWhite), Color::Named(NamedColor::Black));
+/// println!("{}title{RESET}", fg(text));
+/// println!("{}", "note".styled(Style::new().fg(adaptive(Color::Palette(229), Color::Palette(94)))));
+/// # }
+/// ```
+pub fn adaptive(dark: Color, light: Color) -> Color {
+    match background() {
+        Background::Dark    => dark,
+        Background::Light   => light,
+    }
+}
+
+/// The error returned when a string can't be parsed into a `Color`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParseColorError {
+    /// The string isn't the name of a color, a palette index, or a hex color.
+    Unrecognized,
+    /// The string is a number, but it's above 255, the last index of the 256-color palette.
+    PaletteOutOfRange,
+    /// The string starts with `#`, but isn't a valid hex color.
+    InvalidHex(ParseHexError),
+}
+
+impl fmt::Display for ParseColorError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseColorError::Unrecognized       => f.write_str("expected a color name, a palette index, or a hex color"),
+            ParseColorError::PaletteOutOfRange  => f.write_str("palette index must be between 0 and 255"),
+            ParseColorError::InvalidHex(e)      => e.fmt(f),
+        }
+    }
+}
+
+impl core::error::Error for ParseColorError {}
+
+/// Parses a color from a string, such as a command-line argument. Accepts these forms:
+///
+/// - The name of a named color, such as `"red"` or `"bright red"`, ignoring ASCII case and with `_`
+///   or `-` allowed in place of the space, so `"Bright_Red"` and `"bright-red"` work too.
+/// - A palette index from 0 to 255, such as `"214"`, for `Color::Palette`.
+/// - A hex color starting with `#`, such as `"#ff2d44"` or `"#f2a"` (see `parse_hex`), for
+///   `Color::Rgb`.
+///
+/// ```
+/// use flower_pot::*;
+///
+/// assert_eq!("bright_red".parse(), Ok(Color::Named(NamedColor::BrightRed)));
+/// assert_eq!("214".parse(), Ok(Color::Palette(214)));
+/// assert_eq!("#ff2d44".parse(), Ok(Color::Rgb(0xff, 0x2d, 0x44)));
+/// assert_eq!("chartreuse".parse::<Color>(), Err(ParseColorError::Unrecognized));
+/// ```
+impl core::str::FromStr for Color {
+    type Err = ParseColorError;
+
+    fn from_str(s: &str) -> Result<Color, ParseColorError> {
+        if s.starts_with('#') {
+            let (r, g, b) = parse_hex(s).map_err(ParseColorError::InvalidHex)?;
+            Ok(Color::Rgb(r, g, b))
+        } else if !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit()) {
+            s.parse().map(Color::Palette).map_err(|_| ParseColorError::PaletteOutOfRange)
+        } else {
+            NamedColor::from_name(s).map(Color::Named).ok_or(ParseColorError::Unrecognized)
+        }
+    }
+}
+
+impl From<NamedColor> for Color {
+    fn from(color: NamedColor) -> Color {
+        Color::Named(color)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn named_color_codes() {
+        assert_eq!(NamedColor::all().len(), 16);
+        assert_eq!(NamedColor::Red.fg(), RED);
+        assert_eq!(NamedColor::BrightCyan.bg(), BRIGHT_CYAN_BG);
+        assert_eq!(NamedColor::BrightBlack.name(), "bright black");
+
+        for &color in NamedColor::all() {
+            assert_eq!(color.fg(), Style::new().fg(Color::Named(color)).to_string());
+            assert_eq!(color.bg(), Style::new().bg(Color::Named(color)).to_string());
+            assert_eq!(palette_to_rgb(bright_fallback(color)), color.to_rgb());
+        }
+    }
+
+    #[test]
+    fn named_tables() {
+        for (n, &color) in NamedColor::all().iter().enumerate() {
+            assert_eq!(NAMED_FG[n],         color.fg());
+            assert_eq!(NAMED_BG[n],         color.bg());
+            assert_eq!(named_fg(n as u8),   color.fg());
+            assert_eq!(named_bg(n as u8),   color.bg());
+        }
+        assert_eq!(NAMED_FG[0],     BLACK);
+        assert_eq!(NAMED_BG[15],    BRIGHT_WHITE_BG);
+        assert_eq!(named_fg(16),    BRIGHT_WHITE);
+        assert_eq!(named_bg(255),   BRIGHT_WHITE_BG);
+    }
+
+    #[test]
+    fn color_on_background() {
+        let pair = Color::Palette(214).on(Color::Rgb(0, 0, 0));
+        assert_eq!(pair, Style::new().fg(Color::Palette(214)).bg(Color::Rgb(0, 0, 0)));
+        assert_eq!(pair.to_string(), "\x1b[38;5;214;48;2;0;0;0m");
+        assert_eq!(Color::Named(NamedColor::Red).on(Color::Named(NamedColor::White)).to_string(), "\x1b[31;47m");
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn color_free_functions() {
+        assert_eq!(fg(Color::Named(NamedColor::Yellow)),        YELLOW);
+        assert_eq!(fg(Color::Palette(214)),                     color_256(214));
+        assert_eq!(bg(Color::Named(NamedColor::BrightBlue)),    BRIGHT_BLUE_BG);
+        assert_eq!(bg(Color::Rgb(1, 2, 3)),                     truecolor_bg(1, 2, 3));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn cow_codes() {
+        let colors = [Color::Named(NamedColor::BrightCyan), Color::Palette(214), Color::Rgb(1, 2, 3)];
+        for color in colors {
+            assert_eq!(fg_code(color), Style::new().fg(color).to_string());
+            assert_eq!(bg_code(color), Style::new().bg(color).to_string());
+            assert_eq!(matches!(fg_code(color), Cow::Borrowed(_)), !matches!(color, Color::Rgb(..)));
+            assert_eq!(matches!(bg_code(color), Cow::Borrowed(_)), !matches!(color, Color::Rgb(..)));
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn dim_colors() {
+        let no_attributes = Capabilities { attributes_ok: false, ..Capabilities::ALL };
+        let palette = Capabilities { truecolor: false, attributes_ok: false, ..Capabilities::ALL };
+        let basic = Capabilities { basic_color: true, attributes_ok: true, ..Capabilities::NONE };
+
+        assert_eq!(dim_fg(Color::Named(NamedColor::Red)),                                     "\x1b[2;31m");
+        assert_eq!(dim_fg(Color::Palette(214)),                                               "\x1b[2;38;5;214m");
+        assert_eq!(dim_fg_with(Color::Rgb(255, 0, 0), Some(&basic)),                          "\x1b[2;91m");
+        assert_eq!(dim_fg_with(Color::Rgb(200, 100, 0), Some(&no_attributes)),                truecolor(120, 60, 0));
+        assert_eq!(dim_fg_with(Color::Named(NamedColor::BrightWhite), Some(&no_attributes)),  truecolor(153, 153, 153));
+        assert_eq!(dim_fg_with(Color::Named(NamedColor::BrightWhite), Some(&palette)),        color_256(246));
+        assert_eq!(dim_fg_with(Color::Rgb(255, 0, 0), Some(&Capabilities::NONE)),             "");
+    }
+
+    #[test]
+    fn inverting_colors() {
+        let named = |c| Color::Named(c);
+
+        assert_eq!(Color::Rgb(0, 0, 0).invert(),                    Color::Rgb(255, 255, 255));
+        assert_eq!(Color::Rgb(12, 200, 255).invert(),               Color::Rgb(243, 55, 0));
+        assert_eq!(named(NamedColor::Red).invert(),                 named(NamedColor::BrightCyan));
+        assert_eq!(named(NamedColor::BrightWhite).invert(),         named(NamedColor::Black));
+        assert_eq!(Color::Palette(16).invert(),                     Color::Palette(231));
+        assert_eq!(Color::Palette(196).invert(),                    Color::Palette(51));
+        assert_eq!(Color::Palette(232).invert(),                    Color::Palette(231));
+
+        assert_eq!(Color::Rgb(10, 10, 40).contrasting(),            Color::Rgb(255, 255, 255));
+        assert_eq!(Color::Rgb(255, 255, 0).contrasting(),           Color::Rgb(0, 0, 0));
+        assert_eq!(named(NamedColor::Blue).contrasting(),           named(NamedColor::BrightWhite));
+        assert_eq!(named(NamedColor::BrightYellow).contrasting(),   named(NamedColor::Black));
+        assert_eq!(Color::Palette(17).contrasting(),                Color::Palette(231));
+
+        assert_eq!(Color::Palette(9).to_rgb(),                      (255, 0, 0));
+        assert_eq!(named(NamedColor::Blue).to_rgb(),                (0, 0, 238));
+    }
+
+    #[test]
+    fn colors_from_strings() {
+        let parse = |s: &str| s.parse::<Color>();
+
+        assert_eq!(parse("red"),            Ok(Color::Named(NamedColor::Red)));
+        assert_eq!(parse("RED"),            Ok(Color::Named(NamedColor::Red)));
+        assert_eq!(parse("bright red"),     Ok(Color::Named(NamedColor::BrightRed)));
+        assert_eq!(parse("Bright_Blue"),    Ok(Color::Named(NamedColor::BrightBlue)));
+        assert_eq!(parse("bright-white"),   Ok(Color::Named(NamedColor::BrightWhite)));
+        assert_eq!(parse("0"),              Ok(Color::Palette(0)));
+        assert_eq!(parse("255"),            Ok(Color::Palette(255)));
+        assert_eq!(parse("#FF2D44"),        Ok(Color::Rgb(0xff, 0x2d, 0x44)));
+        assert_eq!(parse("#f2a"),           Ok(Color::Rgb(0xff, 0x22, 0xaa)));
+
+        assert_eq!(parse("256"),            Err(ParseColorError::PaletteOutOfRange));
+        assert_eq!(parse("#ff2d4"),         Err(ParseColorError::InvalidHex(ParseHexError::InvalidLength(5))));
+        assert_eq!(parse("ff2d44"),         Err(ParseColorError::Unrecognized));
+        assert_eq!(parse("brightred"),      Err(ParseColorError::Unrecognized));
+        assert_eq!(parse("-1"),             Err(ParseColorError::Unrecognized));
+        assert_eq!(parse(""),               Err(ParseColorError::Unrecognized));
+    }
+}
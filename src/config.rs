@@ -0,0 +1,644 @@
+// Global configuration for the gated emitters.
+
+use core::fmt::{self, Write};
+use core::sync::atomic::{AtomicBool, Ordering};
+
+#[cfg(feature = "alloc")]
+use alloc::string::ToString;
+
+#[cfg(feature = "std")]
+use core::sync::atomic::AtomicU8;
+
+use crate::{palette_to_rgb, rgb_to_256, rgb_to_named_bright, Color, SgrParam, RESET};
+#[cfg(feature = "alloc")]
+use crate::scan::{sgr_params, tokens, Token};
+#[cfg(feature = "alloc")]
+use crate::sgr::parse_sgr_params;
+
+static COLORS_ENABLED: AtomicBool = AtomicBool::new(true);
+static ATTRIBUTES_ENABLED: AtomicBool = AtomicBool::new(true);
+
+/// Turn colors on or off for the gated emitters: the `Styled` wrapper (and so the `styled`
+/// method) and the `style!`, `println_styled!`, and `eprintln_styled!` macros. While colors are
+/// off, these leave out the parts of their styles that set colors (foreground, background, and
+/// underline colors), but keep the text attributes such as bold, unless those are turned off too
+/// (see `set_attributes_enabled`). SGR control codes in a style that can't be parsed are left out
+/// too, since they might set colors. While both are off, they output their content with no control
+/// codes around it. Colors are on by default; `auto_configure` can turn them off based on the
+/// environment. To turn off all styling for just part of the output, use `suppress_colors`.
+///
+/// The constants and the plain functions such as `truecolor` are never affected, because they are
+/// just control codes; if you use them directly, it's up to you to check `colors_enabled`.
+pub fn set_colors_enabled(enabled: bool) {
+    COLORS_ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// Whether colors are currently on for the gated emitters (see `set_colors_enabled`).
+pub fn colors_enabled() -> bool {
+    COLORS_ENABLED.load(Ordering::Relaxed)
+}
+
+/// Turn text attributes (everything other than colors, such as bold, italic, and blinking) on or
+/// off for the gated emitters, independently of colors, for terminals that render some attributes
+/// badly. See `set_colors_enabled` for which emitters are affected; while attributes are off, they
+/// leave out the parts of their styles that set attributes. The `attr` function is also affected.
+/// Attributes are on by default; `auto_configure` turns them off whenever it turns colors off.
+pub fn set_attributes_enabled(enabled: bool) {
+    ATTRIBUTES_ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// Whether text attributes are currently on for the gated emitters (see
+/// `set_attributes_enabled`).
+pub fn attributes_enabled() -> bool {
+    ATTRIBUTES_ENABLED.load(Ordering::Relaxed)
+}
+
+/// Return `code` (one of the attribute constants, such as `BOLD` or `ITALIC`) if text attributes
+/// are turned on, or the empty string if they are turned off (see `set_attributes_enabled`).
+///
+/// ```
+/// use flower_pot::*;
+///
+/// println!("{}important{RESET}", attr(BOLD));
+/// ```
+pub fn attr(code: &str) -> &str {
+    if attributes_enabled() { code } else { "" }
+}
+
+/// Turn off both colors and text attributes for the gated emitters (see `set_colors_enabled` and
+/// `set_attributes_enabled`) until the returned guard is dropped, which turns each of them back
+/// on or off as it was before. This is useful for a stretch of output that must not contain any
+/// control codes, such as JSON for another program to read.
+///
+/// ```no_run
+/// use flower_pot::*;
+///
+/// let warning = Style::new().bold().fg(Color::Named(NamedColor::Yellow));
+/// {
+///     let _plain = suppress_colors();
+///     println!("{{\"status\": \"{}\"}}", "degraded".styled(warning)); // no control codes
+/// }
+/// println!("{}", "degraded".styled(warning)); // styled again
+/// ```
+///
+/// The switches are global, not per-thread, so while the guard is alive, styling is off for every
+/// thread, not just the one that holds the guard. Guards can be nested, as long as they are dropped
+/// in the reverse of the order they were created in (which happens automatically for guards in
+/// nested scopes on one thread). Guards that overlap in some other way, such as guards held by
+/// different threads, can restore the switches in the wrong order and leave styling off.
+pub fn suppress_colors() -> SuppressColorsGuard {
+    let guard = SuppressColorsGuard { colors: colors_enabled(), attributes: attributes_enabled() };
+    set_colors_enabled(false);
+    set_attributes_enabled(false);
+    guard
+}
+
+/// The guard returned by `suppress_colors`, which turns colors and text attributes back on or off
+/// as they were before when it is dropped.
+#[must_use = "styling is only suppressed until the guard is dropped"]
+#[derive(Debug)]
+pub struct SuppressColorsGuard {
+    colors:     bool,
+    attributes: bool,
+}
+
+impl Drop for SuppressColorsGuard {
+    fn drop(&mut self) {
+        set_colors_enabled(self.colors);
+        set_attributes_enabled(self.attributes);
+    }
+}
+
+/// Whether `param` sets a color, as opposed to a text attribute. A reset is neither. An extended
+/// color with missing or out-of-range parameters counts as a color.
+fn is_color_param(param: SgrParam) -> bool {
+    matches!(param,
+        SgrParam::Foreground(_)         | SgrParam::DefaultForeground       |
+        SgrParam::Background(_)         | SgrParam::DefaultBackground       |
+        SgrParam::UnderlineColor(_)     | SgrParam::DefaultUnderlineColor   |
+        SgrParam::Unknown(38 | 48 | 58)
+    )
+}
+
+/// Write `style` to `f` for a gated emitter, leaving out the parts that are turned off (see
+/// `set_colors_enabled` and `set_attributes_enabled`) and using the form chosen with
+/// `set_csi_form`, and return whether anything was written (in which case the emitter
+/// should write a `RESET` after its content, with `write_reset`). Without the `alloc` feature, the
+/// style can't be taken apart, so it is left out entirely unless both colors and attributes are on.
+pub(crate) fn write_gated(f: &mut impl fmt::Write, style: impl fmt::Display) -> Result<bool, fmt::Error> {
+    match csi_form() {
+        CsiForm::TwoByte    => write_enabled_parts(f, style),
+        CsiForm::SingleByte => {
+            let mut out = SingleByteCsi { out: f, escape: false };
+            let wrote = write_enabled_parts(&mut out, style)?;
+            out.finish()?;
+            Ok(wrote)
+        }
+    }
+}
+
+/// Write the parts of `style` that are turned on to `f` (see `write_gated`).
+fn write_enabled_parts(f: &mut impl fmt::Write, style: impl fmt::Display) -> Result<bool, fmt::Error> {
+    let (colors, attributes) = (colors_enabled(), attributes_enabled());
+    if colors && attributes {
+        let mut out = Written { out: f, wrote: false };
+        write!(out, "{style}")?;
+        return Ok(out.wrote);
+    }
+
+    if !colors && !attributes {
+        return Ok(false);
+    }
+
+    #[cfg(feature = "alloc")]
+    {
+        let code = style.to_string();
+        let mut wrote = false;
+
+        for token in tokens(&code) {
+            let params = match token {
+                Token::Escape(escape) => sgr_params(escape).map(parse_sgr_params),
+                Token::Text(_) => None,
+            };
+
+            let Some(params) = params else {
+                // Other control codes (and any text) aren't colors or attributes, so keep them.
+                let (Token::Escape(s) | Token::Text(s)) = token;
+                f.write_str(s)?;
+                wrote = true;
+                continue;
+            };
+
+            // An SGR control code that can't be parsed might set either, so leave it out.
+            let Ok(params) = params else { continue };
+
+            let mut first = true;
+            for param in params {
+                if param == SgrParam::Reset || is_color_param(param) == colors {
+                    write!(f, "{}{param}", if first { "\x1b[" } else { ";" })?;
+                    first = false;
+                }
+            }
+
+            if !first {
+                f.write_str("m")?;
+                wrote = true;
+            }
+        }
+
+        Ok(wrote)
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    Ok(false)
+}
+
+/// The characters that start a control code, as chosen with `set_csi_form`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum CsiForm {
+    /// The standard two-character form `\x1b[` (the escape character followed by `[`), which
+    /// every terminal understands. This is the default.
+    #[default]
+    TwoByte,
+    /// The single C1 control character CSI, U+009B, in place of each `\x1b[`. In 8-bit character
+    /// sets this is the single byte 0x9B, but this crate's output is always UTF-8 text, where
+    /// U+009B is encoded as the two bytes 0xC2 0x9B. Most terminals that decode UTF-8 don't treat
+    /// that as a control code (and a bare 0x9B byte isn't valid UTF-8), so this form is
+    /// incompatible with ordinary UTF-8 terminals and is only for specialized hosts that are known
+    /// to accept it.
+    SingleByte,
+}
+
+static SINGLE_BYTE_CSI: AtomicBool = AtomicBool::new(false);
+
+/// Choose how the gated emitters (see `set_colors_enabled`) start their control codes, including
+/// the trailing `RESET`. The default is `CsiForm::TwoByte`, the standard `\x1b[`; only choose
+/// `CsiForm::SingleByte` if you know the host understands it (see its documentation). The
+/// constants and the plain functions such as `truecolor` always use the standard form.
+pub fn set_csi_form(form: CsiForm) {
+    SINGLE_BYTE_CSI.store(form == CsiForm::SingleByte, Ordering::Relaxed);
+}
+
+/// How the gated emitters currently start their control codes (see `set_csi_form`).
+pub fn csi_form() -> CsiForm {
+    if SINGLE_BYTE_CSI.load(Ordering::Relaxed) { CsiForm::SingleByte } else { CsiForm::TwoByte }
+}
+
+/// Passes text on to `out`, keeping track of whether there was any.
+struct Written<'a, W: fmt::Write> {
+    out:    &'a mut W,
+    wrote:  bool,
+}
+
+impl<W: fmt::Write> fmt::Write for Written<'_, W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.wrote |= !s.is_empty();
+        self.out.write_str(s)
+    }
+}
+
+/// Passes text on to `out`, replacing each `\x1b[` with the single-character CSI.
+struct SingleByteCsi<'a, W: fmt::Write> {
+    out:    &'a mut W,
+    escape: bool,
+}
+
+impl<W: fmt::Write> SingleByteCsi<'_, W> {
+    /// Write out an escape character held back at the end of the text so far.
+    fn finish(self) -> fmt::Result {
+        if self.escape { self.out.write_char('\x1b') } else { Ok(()) }
+    }
+}
+
+impl<W: fmt::Write> fmt::Write for SingleByteCsi<'_, W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for c in s.chars() {
+            if self.escape {
+                self.escape = false;
+                if c == '[' {
+                    self.out.write_char('\u{9b}')?;
+                    continue;
+                }
+                self.out.write_char('\x1b')?;
+            }
+
+            if c == '\x1b' {
+                self.escape = true;
+            } else {
+                self.out.write_char(c)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Write `RESET` for a gated emitter, in the form chosen with `set_csi_form`.
+pub(crate) fn write_reset(f: &mut impl fmt::Write) -> fmt::Result {
+    match csi_form() {
+        CsiForm::TwoByte    => f.write_str(RESET),
+        CsiForm::SingleByte => f.write_str("\u{9b}0m"),
+    }
+}
+
+static LEGACY_BRIGHT: AtomicBool = AtomicBool::new(false);
+
+/// Tell `bright_fg_adaptive`, `bright_bg_adaptive`, and `bright_bg_256_adaptive` whether the
+/// terminal is a legacy terminal that doesn't understand the bright color codes (90–97, such as
+/// `BRIGHT_RED`, and 100–107, such as `BRIGHT_RED_BG`). By default, the terminal is assumed to
+/// understand them.
+pub fn set_legacy_bright(legacy: bool) {
+    LEGACY_BRIGHT.store(legacy, Ordering::Relaxed);
+}
+
+/// Whether `bright_fg_adaptive` and the other adaptive functions are currently falling back from
+/// the bright color codes (see `set_legacy_bright`).
+pub fn legacy_bright() -> bool {
+    LEGACY_BRIGHT.load(Ordering::Relaxed)
+}
+
+/// The old name of `set_legacy_bright`, from when it only affected background colors.
+#[deprecated(note = "renamed to `set_legacy_bright`, since it affects foreground colors too")]
+pub fn set_legacy_bright_bg(legacy: bool) {
+    set_legacy_bright(legacy);
+}
+
+/// The old name of `legacy_bright`, from when it only affected background colors.
+#[deprecated(note = "renamed to `legacy_bright`, since it affects foreground colors too")]
+pub fn legacy_bright_bg() -> bool {
+    legacy_bright()
+}
+
+/// Whether the terminal has a dark or a light background. Used by `adaptive`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Background {
+    /// A dark background, with light text. This is the default.
+    #[default]
+    Dark,
+    /// A light background, with dark text.
+    Light,
+}
+
+static LIGHT_BACKGROUND: AtomicBool = AtomicBool::new(false);
+
+/// Tell `adaptive` whether the terminal has a dark or a light background. By default, the
+/// background is assumed to be dark.
+pub fn set_background(background: Background) {
+    LIGHT_BACKGROUND.store(background == Background::Light, Ordering::Relaxed);
+}
+
+/// The kind of background `adaptive` is currently choosing colors for (see `set_background`).
+pub fn background() -> Background {
+    if LIGHT_BACKGROUND.load(Ordering::Relaxed) { Background::Light } else { Background::Dark }
+}
+
+/// Decide whether styling should be on, given the values of the relevant environment variables
+/// and whether stdout is a terminal. See `auto_configure` for the rules.
+#[cfg(feature = "std")]
+fn detect(no_color: Option<&str>, colorterm: Option<&str>, term: Option<&str>, is_terminal: bool) -> bool {
+    let set = |var: Option<&str>| var.is_some_and(|v| !v.is_empty());
+
+    if set(no_color) || !is_terminal {
+        false
+    } else if set(colorterm) {
+        true
+    } else {
+        match term {
+            None | Some("")     => cfg!(windows),
+            Some(term)          => term != "dumb",
+        }
+    }
+}
+
+/// Turn styling (both colors and text attributes) on or off for the gated emitters (see
+/// `set_colors_enabled` and `set_attributes_enabled`) based on the environment, and return whether
+/// it ended up on. Call this once at the start of your program.
+/// Requires the `std` feature. The rules are checked in this order, and the first one that applies
+/// decides:
+///
+/// 1. If the `NO_COLOR` environment variable is set to anything other than the empty string,
+///    styling is turned off (see <https://no-color.org>).
+/// 2. If stdout is not a terminal (for example, because it is piped into a file or another
+///    program), styling is turned off.
+/// 3. If the `COLORTERM` environment variable is set to anything other than the empty string, the
+///    terminal has advertised color support, so styling is turned on.
+/// 4. If the `TERM` environment variable is `dumb`, the terminal has advertised that it doesn't
+///    understand control codes, so styling is turned off. This is common in editors' built-in
+///    shells and in some CI systems, which would otherwise show the control codes as garbage.
+/// 5. If the `TERM` environment variable isn't set (or is empty), the terminal hasn't advertised
+///    anything, so styling is turned off, except on Windows, where the console doesn't set `TERM`
+///    (see also `enable_vt_processing`).
+/// 6. Otherwise, styling is turned on.
+///
+/// So `NO_COLOR` wins over everything else, and `COLORTERM` wins over `TERM`.
+///
+/// Colors and attributes are always turned on or off together; in particular, `NO_COLOR` turns
+/// off attributes too. You can still call `set_colors_enabled` or `set_attributes_enabled`
+/// afterwards to override the result, for example to honor a `--color=always` command-line flag,
+/// or to keep bold text when `NO_COLOR` is set.
+#[cfg(feature = "std")]
+pub fn auto_configure() -> bool {
+    use std::io::IsTerminal;
+
+    let var = |name: &str| std::env::var(name).ok();
+    let enabled = detect(
+        var("NO_COLOR").as_deref(),
+        var("COLORTERM").as_deref(),
+        var("TERM").as_deref(),
+        std::io::stdout().is_terminal(),
+    );
+
+    set_colors_enabled(enabled);
+    set_attributes_enabled(enabled);
+    enabled
+}
+
+// Whether the terminal supports the 256-color palette: UNKNOWN until it has been detected or set.
+#[cfg(feature = "std")]
+const UNKNOWN:      u8 = 0;
+#[cfg(feature = "std")]
+const UNSUPPORTED:  u8 = 1;
+#[cfg(feature = "std")]
+const SUPPORTED:    u8 = 2;
+
+#[cfg(feature = "std")]
+static SUPPORTS_256_COLOR: AtomicU8 = AtomicU8::new(UNKNOWN);
+
+// What was detected from the environment, kept separately so that detection runs at most once even
+// if several threads call `supports_256_color` for the first time at once.
+#[cfg(feature = "std")]
+static DETECTED_256_COLOR: std::sync::OnceLock<bool> = std::sync::OnceLock::new();
+
+/// Decide whether the terminal supports the 256-color palette, given the values of the relevant
+/// environment variables. See `supports_256_color` for the rules.
+#[cfg(feature = "std")]
+fn detect_256_color(term: Option<&str>, colorterm: Option<&str>) -> bool {
+    let term = term.unwrap_or("");
+    let colorterm = colorterm.unwrap_or("");
+
+    term.contains("256color") || term.contains("direct") || colorterm == "truecolor" || colorterm == "24bit"
+}
+
+/// Whether the terminal advertises support for the 256-color palette (see `color_256`), so that
+/// you can fall back to the 16 named colors if it doesn't. Requires the `std` feature. Support is
+/// detected from the environment the first time this is called, and the result is cached for all
+/// later calls. The terminal counts as supporting the palette if:
+///
+/// - the `TERM` environment variable contains `256color` (as in `xterm-256color`,
+///   `screen-256color`, and `tmux-256color`) or `direct` (as in `xterm-direct`, which advertises
+///   truecolor support); or
+/// - the `COLORTERM` environment variable is `truecolor` or `24bit`, since truecolor terminals
+///   also support the palette.
+///
+/// Calling `set_supports_256_color` replaces the cached value (whether or not it has been detected
+/// yet), so later calls return the value you set instead of looking at the environment. This holds
+/// even if another thread is detecting the value at the same time: a detected value never replaces
+/// one that has been set. The environment is only ever looked at once, however many threads call
+/// this at the same time.
+#[cfg(feature = "std")]
+pub fn supports_256_color() -> bool {
+    match SUPPORTS_256_COLOR.load(Ordering::Relaxed) {
+        SUPPORTED   => true,
+        UNSUPPORTED => false,
+        _           => {
+            let supported = *DETECTED_256_COLOR.get_or_init(|| {
+                let var = |name: &str| std::env::var(name).ok();
+                detect_256_color(var("TERM").as_deref(), var("COLORTERM").as_deref())
+            });
+            let detected = if supported { SUPPORTED } else { UNSUPPORTED };
+            match SUPPORTS_256_COLOR.compare_exchange(UNKNOWN, detected, Ordering::Relaxed, Ordering::Relaxed) {
+                Ok(_)       => supported,
+                Err(set)    => set == SUPPORTED,
+            }
+        }
+    }
+}
+
+/// Override the result of `supports_256_color`, for example in tests or to honor a command-line
+/// flag. Requires the `std` feature.
+#[cfg(feature = "std")]
+pub fn set_supports_256_color(supported: bool) {
+    SUPPORTS_256_COLOR.store(if supported { SUPPORTED } else { UNSUPPORTED }, Ordering::Relaxed);
+}
+
+/// Decide whether the terminal supports truecolor, given the values of the relevant environment
+/// variables. See `Capabilities::detect` for the rules.
+#[cfg(feature = "std")]
+fn detect_truecolor(term: Option<&str>, colorterm: Option<&str>) -> bool {
+    term.unwrap_or("").contains("direct") || matches!(colorterm, Some("truecolor" | "24bit"))
+}
+
+/// What a terminal can display, for choosing control codes for a particular terminal (such as the
+/// other end of a remote session, or a made-up terminal in a test) instead of the one this process
+/// is attached to. Detect the capabilities of the current terminal with `Capabilities::detect`,
+/// or fill in the fields yourself, starting from `Capabilities::ALL` or `Capabilities::NONE` and
+/// overriding fields as needed. `fg_with` and `bg_with` use a `Capabilities` to pick colors the
+/// terminal can display.
+///
+/// ```
+/// use flower_pot::*;
+/// # #[cfg(feature = "alloc")] {
+///
+/// let old_terminal = Capabilities { basic_color: true, ..Capabilities::NONE };
+/// assert_eq!(fg_with(Color::Rgb(255, 0, 0), Some(&old_terminal)), BRIGHT_RED);
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Capabilities {
+    /// Whether the terminal supports RGB colors (see `truecolor`).
+    pub truecolor:      bool,
+    /// Whether the terminal supports the 256-color palette (see `color_256`).
+    pub color256:       bool,
+    /// Whether the terminal supports the 16 named colors (such as `RED` and `BRIGHT_RED`).
+    pub basic_color:    bool,
+    /// Whether the terminal supports text attributes (such as `BOLD` and `ITALIC`).
+    pub attributes_ok:  bool,
+}
+
+impl Capabilities {
+    /// A terminal that supports everything.
+    pub const ALL: Capabilities = Capabilities {
+        truecolor:      true,
+        color256:       true,
+        basic_color:    true,
+        attributes_ok:  true,
+    };
+
+    /// A terminal that supports no styling at all.
+    pub const NONE: Capabilities = Capabilities {
+        truecolor:      false,
+        color256:       false,
+        basic_color:    false,
+        attributes_ok:  false,
+    };
+
+    /// Detect the capabilities of the terminal on stdout from the environment. Requires the `std`
+    /// feature. The fields are filled in as follows:
+    ///
+    /// - `basic_color` and `attributes_ok` are both true if `auto_configure` would turn styling on
+    ///   (see there for the rules, which look at `NO_COLOR`, `COLORTERM`, and `TERM`, and at
+    ///   whether stdout is a terminal), and both false otherwise.
+    /// - `color256` is true if `basic_color` is true and the terminal advertises support for the
+    ///   palette, by the rules of `supports_256_color` (which look at `TERM` and `COLORTERM`).
+    /// - `truecolor` is true if `basic_color` is true and either the `COLORTERM` environment
+    ///   variable is `truecolor` or `24bit`, or the `TERM` environment variable contains `direct`
+    ///   (as in `xterm-direct`).
+    ///
+    /// Unlike `auto_configure` and `supports_256_color`, this doesn't change or cache anything.
+    #[cfg(feature = "std")]
+    pub fn detect() -> Capabilities {
+        use std::io::IsTerminal;
+
+        let var = |name: &str| std::env::var(name).ok();
+        Capabilities::from_env(
+            var("NO_COLOR").as_deref(),
+            var("COLORTERM").as_deref(),
+            var("TERM").as_deref(),
+            std::io::stdout().is_terminal(),
+        )
+    }
+
+    /// Decide the capabilities given the values of the relevant environment variables and whether
+    /// stdout is a terminal. See `Capabilities::detect` for the rules.
+    #[cfg(feature = "std")]
+    fn from_env(no_color: Option<&str>, colorterm: Option<&str>, term: Option<&str>, is_terminal: bool) -> Capabilities {
+        let enabled = detect(no_color, colorterm, term, is_terminal);
+        Capabilities {
+            truecolor:      enabled && detect_truecolor(term, colorterm),
+            color256:       enabled && detect_256_color(term, colorterm),
+            basic_color:    enabled,
+            attributes_ok:  enabled,
+        }
+    }
+
+    /// The closest color to `color` that the terminal can display, or `None` if it can't display
+    /// colors at all. RGB colors fall back to the nearest entry of the 256-color palette (see
+    /// `rgb_to_256`), and RGB and palette colors fall back to the nearest of the 16 named colors
+    /// (see `rgb_to_named_bright`). Colors the terminal supports are returned unchanged.
+    ///
+    /// ```
+    /// use flower_pot::*;
+    ///
+    /// let caps = Capabilities { truecolor: false, ..Capabilities::ALL };
+    /// assert_eq!(caps.color(Color::Rgb(255, 0, 0)), Some(Color::Palette(196)));
+    /// assert_eq!(Capabilities::NONE.color(Color::Palette(196)), None);
+    /// ```
+    pub fn color(&self, color: Color) -> Option<Color> {
+        let named = |(r, g, b)| Color::Named(rgb_to_named_bright(r, g, b));
+        match color {
+            _ if !self.basic_color                      => None,
+            Color::Rgb(..) if self.truecolor            => Some(color),
+            Color::Rgb(r, g, b) if self.color256        => Some(Color::Palette(rgb_to_256(r, g, b))),
+            Color::Rgb(r, g, b)                         => Some(named((r, g, b))),
+            Color::Palette(n) if !self.color256         => Some(named(palette_to_rgb(n))),
+            _                                           => Some(color),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::NamedColor;
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn detection_precedence() {
+        assert!( detect(None,       None,               Some("xterm"),  true));
+        assert!( detect(Some(""),   None,               Some("xterm"),  true));
+        assert!(!detect(Some("1"),  Some("truecolor"),  Some("xterm"),  true));
+        assert!(!detect(None,       Some("truecolor"),  Some("xterm"),  false));
+        assert!( detect(None,       Some("truecolor"),  Some("dumb"),   true));
+        assert!(!detect(None,       None,               Some("dumb"),   true));
+        assert!(!detect(None,       Some(""),           Some("dumb"),   true));
+        assert!( detect(None,       Some("truecolor"),  None,           true));
+        assert!( detect(None,       None,               Some("vt100"),  true));
+
+        // The Windows console doesn't set `TERM`.
+        assert_eq!(detect(None, None, None, true),      cfg!(windows));
+        assert_eq!(detect(None, None, Some(""), true),  cfg!(windows));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn detection_256_color() {
+        assert!( detect_256_color(Some("xterm-256color"),  None));
+        assert!( detect_256_color(Some("tmux-256color"),   Some("")));
+        assert!( detect_256_color(Some("xterm-direct"),    None));
+        assert!( detect_256_color(Some("xterm"),           Some("truecolor")));
+        assert!( detect_256_color(None,                    Some("24bit")));
+        assert!(!detect_256_color(Some("xterm"),           None));
+        assert!(!detect_256_color(Some("dumb"),            Some("yes")));
+        assert!(!detect_256_color(None,                    None));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn capabilities_from_env() {
+        let caps = |no_color, colorterm, term, is_terminal| Capabilities::from_env(no_color, colorterm, term, is_terminal);
+        let basic = Capabilities { basic_color: true, attributes_ok: true, ..Capabilities::NONE };
+
+        assert_eq!(caps(None,       Some("truecolor"),  Some("xterm-256color"), true),  Capabilities::ALL);
+        assert_eq!(caps(None,       None,               Some("xterm-direct"),   true),  Capabilities::ALL);
+        assert_eq!(caps(None,       None,               Some("xterm-256color"), true),  Capabilities { truecolor: false, ..Capabilities::ALL });
+        assert_eq!(caps(None,       None,               Some("xterm"),          true),  basic);
+        assert_eq!(caps(None,       None,               Some("dumb"),           true),  Capabilities::NONE);
+        assert_eq!(caps(Some("1"),  Some("truecolor"),  Some("xterm-256color"), true),  Capabilities::NONE);
+        assert_eq!(caps(None,       Some("truecolor"),  Some("xterm-256color"), false), Capabilities::NONE);
+    }
+
+    #[test]
+    fn capabilities_downgrade_colors() {
+        let palette = Capabilities { truecolor: false, ..Capabilities::ALL };
+        let basic = Capabilities { basic_color: true, ..Capabilities::NONE };
+        let (rgb, orange, red) = (Color::Rgb(0, 0, 250), Color::Palette(208), Color::Named(NamedColor::Red));
+
+        assert_eq!(Capabilities::ALL.color(rgb),    Some(rgb));
+        assert_eq!(palette.color(rgb),              Some(Color::Palette(21)));
+        assert_eq!(palette.color(orange),           Some(orange));
+        assert_eq!(basic.color(rgb),                Some(Color::Named(NamedColor::Blue)));
+        assert_eq!(basic.color(Color::Palette(1)),  Some(red));
+        assert_eq!(basic.color(orange),             Some(Color::Named(NamedColor::Yellow)));
+        assert_eq!(basic.color(red),                Some(red));
+        assert_eq!(Capabilities::NONE.color(red),   None);
+    }
+}
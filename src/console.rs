@@ -0,0 +1,82 @@
+// Turning on support for control codes in the Windows console.
+
+use std::io;
+
+/// Turn on support for control codes in the Windows console that stdout is attached to, and return
+/// whether it is on. Requires the `std` feature. Older Windows consoles (before Windows 10) print
+/// control codes as garbage, and newer ones only interpret them once "virtual terminal processing"
+/// has been turned on for the console, which most programs don't do, so call this at the start of
+/// your program, before printing any control codes:
+///
+/// - If the console supports control codes, this turns on virtual terminal processing with
+///   `SetConsoleMode` (if it isn't on already) and returns `Ok(true)`.
+/// - If the console doesn't support control codes, or stdout isn't a console at all (for example,
+///   because it is redirected into a file), this returns `Ok(false)`. You should then turn styling
+///   off with `set_colors_enabled(false)` and `set_attributes_enabled(false)`.
+/// - If the console couldn't be checked, this returns the error.
+///
+/// Everywhere other than Windows, terminals interpret control codes without being asked to, so
+/// this does nothing and returns `Ok(true)`.
+///
+/// ```no_run
+/// use flower_pot::*;
+///
+/// if !enable_vt_processing().unwrap_or(false) {
+///     set_colors_enabled(false);
+///     set_attributes_enabled(false);
+/// }
+/// ```
+pub fn enable_vt_processing() -> io::Result<bool> {
+    #[cfg(windows)]
+    return enable_windows_vt_processing();
+
+    #[cfg(not(windows))]
+    Ok(true)
+}
+
+/// `enable_vt_processing` on Windows.
+#[cfg(windows)]
+fn enable_windows_vt_processing() -> io::Result<bool> {
+    use core::ffi::c_void;
+
+    type Handle = *mut c_void;
+
+    const STD_OUTPUT_HANDLE:                    u32 = -11i32 as u32;
+    const INVALID_HANDLE_VALUE:                 Handle = -1isize as Handle;
+    const ENABLE_VIRTUAL_TERMINAL_PROCESSING:   u32 = 0x0004;
+
+    #[link(name = "kernel32")]
+    unsafe extern "system" {
+        fn GetStdHandle(std_handle: u32) -> Handle;
+        fn GetConsoleMode(console: Handle, mode: *mut u32) -> i32;
+        fn SetConsoleMode(console: Handle, mode: u32) -> i32;
+    }
+
+    // SAFETY: these functions have no preconditions beyond their arguments being valid. The handle
+    // comes from `GetStdHandle` and is checked before use, and `mode` is a valid place to write to.
+    unsafe {
+        let console = GetStdHandle(STD_OUTPUT_HANDLE);
+        if console == INVALID_HANDLE_VALUE {
+            return Err(io::Error::last_os_error());
+        }
+
+        let mut mode = 0;
+        if console.is_null() || GetConsoleMode(console, &mut mode) == 0 {
+            return Ok(false);
+        }
+
+        Ok(mode & ENABLE_VIRTUAL_TERMINAL_PROCESSING != 0
+            || SetConsoleMode(console, mode | ENABLE_VIRTUAL_TERMINAL_PROCESSING) != 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(not(windows))]
+    fn vt_processing_is_always_on() {
+        assert!(enable_vt_processing().unwrap());
+    }
+}
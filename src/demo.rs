@@ -0,0 +1,154 @@
+// A printed sample of what the terminal supports.
+
+use std::io;
+
+use crate::*;
+
+/// Write a sample of this crate's styles and colors to `w`, so you can see at a glance which of
+/// them your terminal supports: the text attributes (bold, italic, blinking, and so on), the 16
+/// named colors as foreground and background colors, the whole 256-color palette, and truecolor
+/// gradients. The output ends with `RESET`. Requires the `std` feature.
+///
+/// ```no_run
+/// flower_pot::demo(&mut std::io::stdout()).unwrap();
+/// ```
+pub fn demo(w: &mut impl io::Write) -> io::Result<()> {
+    writeln!(w, "Attributes:")?;
+    let attributes = [
+        (BOLD,              "bold"),
+        (DIM,               "dim"),
+        (ITALIC,            "italic"),
+        (UNDERLINE,         "underline"),
+        (SLOW_BLINK,        "slow blink"),
+        (RAPID_BLINK,       "rapid blink"),
+        (INVERTED,          "inverted"),
+        (HIDDEN,            "hidden"),
+        (STRIKETHROUGH,     "strikethrough"),
+        (DOUBLE_UNDERLINE,  "double underline"),
+        (FRAKTUR,           "Fraktur"),
+        (FRAMED,            "framed"),
+        (ENCIRCLED,         "encircled"),
+        (OVERLINE,          "overline"),
+    ];
+    for (code, name) in attributes {
+        write!(w, " {code}{name}{RESET}")?;
+    }
+    writeln!(w)?;
+    writeln!(w)?;
+
+    writeln!(w, "Named colors:")?;
+    for half in NamedColor::all().chunks(8) {
+        for color in half {
+            write!(w, " {}{:<14}{RESET}", color.fg(), color.name())?;
+        }
+        writeln!(w)?;
+        for color in half {
+            write!(w, " {}{:<14}{RESET}", color.bg(), color.name())?;
+        }
+        writeln!(w)?;
+    }
+    writeln!(w)?;
+
+    writeln!(w, "256-color palette:")?;
+    for n in 0..=255u8 {
+        write!(w, "{} {n:>3} ", color_256_bg_str(n))?;
+        if n % 16 == 15 {
+            writeln!(w, "{RESET}")?;
+        }
+    }
+    writeln!(w)?;
+
+    writeln!(w, "Truecolor:")?;
+    let bar = " ".repeat(64);
+    writeln!(w, "{}", gradient_bg(&bar, (255, 0, 0), (0, 0, 255)))?;
+    writeln!(w, "{}", gradient_bg(&bar, (0, 0, 0), (255, 255, 255)))?;
+    writeln!(w, "{}", rainbow(&"█".repeat(64)))?;
+
+    write!(w, "{RESET}")
+}
+
+/// Write one swatch of `palette_preview`: the palette index `n` in black or white, whichever is
+/// more readable, on a background of that palette color.
+fn write_swatch(w: &mut impl io::Write, n: u8) -> io::Result<()> {
+    let label = if readable_fg(palette_to_rgb(n)) == (0, 0, 0) { 16 } else { 231 };
+    write!(w, "{}{} {n:>3} {RESET}", color_256_bg_str(n), color_256_str(label))
+}
+
+/// Write the 256-color palette to `w` as a grid of swatches, so you can check how your terminal
+/// displays each color: the 16 named colors in two rows of 8, the 6×6×6 color cube as six 6×6
+/// squares (one per level of red, with green going down and blue going across each square; see
+/// `cube`), and the 24 shades of the grayscale ramp in two rows of 12. Each swatch is the palette
+/// color as the background with its index on top, followed by a `RESET`. Requires the `std`
+/// feature.
+///
+/// ```no_run
+/// flower_pot::palette_preview(&mut std::io::stdout()).unwrap();
+/// ```
+pub fn palette_preview(w: &mut impl io::Write) -> io::Result<()> {
+    writeln!(w, "Named colors:")?;
+    for row in [0..8, 8..16] {
+        for n in row {
+            write_swatch(w, n)?;
+        }
+        writeln!(w)?;
+    }
+    writeln!(w)?;
+
+    writeln!(w, "Color cube:")?;
+    for reds in [0..3, 3..6] {
+        for g in 0..6 {
+            for r in reds.clone() {
+                write!(w, " ")?;
+                for b in 0..6 {
+                    write_swatch(w, cube(r, g, b))?;
+                }
+            }
+            writeln!(w)?;
+        }
+    }
+    writeln!(w)?;
+
+    writeln!(w, "Grayscale:")?;
+    for row in [0..12, 12..24] {
+        for level in row {
+            write_swatch(w, 232 + level)?;
+        }
+        writeln!(w)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn demo_output() {
+        let mut out = Vec::new();
+        demo(&mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+
+        assert!(out.ends_with(RESET));
+        for n in 0..=255 {
+            assert!(out.contains(color_256_bg_str(n)));
+        }
+        assert!(out.contains(&truecolor_bg(255, 0, 0)));
+        assert!(out.contains(&format!("{BRIGHT_MAGENTA}bright magenta")));
+    }
+
+    #[test]
+    fn palette_preview_output() {
+        let mut out = Vec::new();
+        palette_preview(&mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+
+        for n in 0..=255 {
+            assert!(out.contains(color_256_bg_str(n)));
+            assert!(out.contains(&format!(" {n:>3} {RESET}")));
+        }
+        assert_eq!(out.matches(RESET).count(), 256);
+        assert!(out.contains(&format!("{}{} 226 {RESET}", color_256_bg_str(226), color_256_str(16))));
+        assert!(out.contains(&format!("{}{}  16 {RESET}", color_256_bg_str(16), color_256_str(231))));
+        assert_eq!(strip_ansi(&out).lines().count(), 2 + 1 + 12 + 1 + 2 + 3);
+    }
+}
@@ -0,0 +1,351 @@
+// Effects that style each character of a string differently.
+
+use core::fmt::Write;
+
+use alloc::format;
+use alloc::string::String;
+
+use crate::{
+    blend_linear, color_256_bg_str, color_256_str, darken, hsl_to_rgb, rgb_to_256, truecolor, truecolor_bg, Color,
+    Style, Styled, RESET,
+};
+
+/// How far `step` is through `steps` evenly-spaced steps, from 0.0 at the first step to 1.0 at the
+/// last. A single step counts as 0.0.
+fn fraction(step: usize, steps: usize) -> f32 {
+    if steps <= 1 {
+        return 0.0;
+    }
+
+    step as f32 / (steps - 1) as f32
+}
+
+/// The color at `step` out of `steps` evenly-spaced steps from `start` to `end` (see
+/// `blend_linear`).
+fn interpolate(start: (u8, u8, u8), end: (u8, u8, u8), step: usize, steps: usize) -> (u8, u8, u8) {
+    blend_linear(start, end, fraction(step, steps))
+}
+
+fn gradient_with(text: &str, start: (u8, u8, u8), end: (u8, u8, u8), code: fn(u8, u8, u8) -> String) -> String {
+    let steps = text.chars().count();
+    if steps == 0 {
+        return String::new();
+    }
+
+    let mut out = String::new();
+    for (i, c) in text.chars().enumerate() {
+        let (r, g, b) = interpolate(start, end, i, steps);
+        out.push_str(&code(r, g, b));
+        out.push(c);
+    }
+    out.push_str(RESET);
+    out
+}
+
+/// Color the text so that its foreground fades from the RGB value `start` at the first character
+/// to the RGB value `end` at the last character, interpolating in linear light between them with
+/// `blend_linear`, so the middle of the fade doesn't come out darker than either end. Each
+/// character gets its own truecolor code, and a single `RESET` is added at the end. An empty
+/// string is returned unchanged. Not supported on all terminals (see `truecolor`).
+pub fn gradient(text: &str, start: (u8, u8, u8), end: (u8, u8, u8)) -> String {
+    gradient_with(text, start, end, truecolor)
+}
+
+/// Like `gradient`, but fades the background color instead of the foreground color.
+pub fn gradient_bg(text: &str, start: (u8, u8, u8), end: (u8, u8, u8)) -> String {
+    gradient_with(text, start, end, truecolor_bg)
+}
+
+fn gradient_256_with(text: &str, start: (u8, u8, u8), end: (u8, u8, u8), code: fn(u8) -> &'static str) -> String {
+    let steps = text.chars().count();
+    if steps == 0 {
+        return String::new();
+    }
+
+    let mut out = String::new();
+    let mut current = None;
+    for (i, c) in text.chars().enumerate() {
+        let (r, g, b) = interpolate(start, end, i, steps);
+        let n = rgb_to_256(r, g, b);
+        if current != Some(n) {
+            out.push_str(code(n));
+            current = Some(n);
+        }
+        out.push(c);
+    }
+    out.push_str(RESET);
+    out
+}
+
+/// Like `gradient`, but for terminals that only support the 256-color palette: each step of the
+/// fade is computed the same way as in `gradient` and then mapped to the nearest palette color
+/// with `rgb_to_256`. A palette code is only added where the palette color changes, so runs of
+/// characters that map to the same color share one code. To fade between two palette colors,
+/// convert them with `palette_to_rgb` first.
+///
+/// ```
+/// use flower_pot::*;
+///
+/// let faded = gradient_256("abc", palette_to_rgb(196), palette_to_rgb(21));
+/// assert!(faded.starts_with(color_256_str(196)));
+/// assert!(faded.ends_with(&format!("{}c{RESET}", color_256_str(21))));
+/// ```
+pub fn gradient_256(text: &str, start: (u8, u8, u8), end: (u8, u8, u8)) -> String {
+    gradient_256_with(text, start, end, color_256_str)
+}
+
+/// Like `gradient_256`, but fades the background color instead of the foreground color.
+pub fn gradient_256_bg(text: &str, start: (u8, u8, u8), end: (u8, u8, u8)) -> String {
+    gradient_256_with(text, start, end, color_256_bg_str)
+}
+
+/// Color each character of the text a different hue, stepping evenly around the color wheel from
+/// red, with a single `RESET` at the end. An empty string is returned unchanged. Equivalent to
+/// `rainbow_with(text, 0.0, 1.0, 0.5)`. Not supported on all terminals (see `truecolor`).
+///
+/// ```
+/// use flower_pot::*;
+///
+/// println!("{}", rainbow("Happy birthday!"));
+/// ```
+pub fn rainbow(text: &str) -> String {
+    rainbow_with(text, 0.0, 1.0, 0.5)
+}
+
+/// Like `rainbow`, but starting at the hue `start_hue` (in degrees) and using the saturation `sat`
+/// and lightness `light` (both between 0.0 and 1.0) for every character. See `hsl_to_rgb` for how
+/// the values are interpreted.
+pub fn rainbow_with(text: &str, start_hue: f32, sat: f32, light: f32) -> String {
+    let steps = text.chars().count();
+    if steps == 0 {
+        return String::new();
+    }
+
+    let mut out = String::new();
+    for (i, c) in text.chars().enumerate() {
+        let (r, g, b) = hsl_to_rgb(start_hue + 360.0 * i as f32 / steps as f32, sat, light);
+        out.push_str(&truecolor(r, g, b));
+        out.push(c);
+    }
+    out.push_str(RESET);
+    out
+}
+
+/// Style each character of the text with the style that `f` returns for it, given the character's
+/// index (counting characters, not bytes) and the character itself, for effects that `gradient`
+/// and `rainbow` don't cover. A control code is only added where the style changes from one
+/// character to the next, and it only changes what differs (see `Style::diff`), so runs of
+/// characters with the same style share one code. A `RESET` is added at the end unless the last
+/// character is unstyled.
+///
+/// ```
+/// use flower_pot::*;
+///
+/// let red = Style::new().fg(Color::Named(NamedColor::Red));
+/// let vowels = |_, c| if "aeiou".contains(c) { red } else { Style::new() };
+///
+/// assert_eq!(map_chars("boat", vowels), format!("b{RED}oa{RESET}t"));
+///
+/// let stripes = map_chars("-----", |i, _| Style::new().bold().when(i % 2 == 0));
+/// println!("{stripes}");
+/// ```
+pub fn map_chars(text: &str, f: impl Fn(usize, char) -> Style) -> String {
+    let mut out = String::new();
+    let mut current = Style::new();
+
+    for (i, c) in text.chars().enumerate() {
+        let style = f(i, c);
+        out.push_str(&current.diff(&style));
+        out.push(c);
+        current = style;
+    }
+
+    if !current.is_empty() {
+        out.push_str(RESET);
+    }
+    out
+}
+
+/// Color the text so that it fades out towards the end, for a trailing or "ghosting" effect: the
+/// first character is in the RGB color `from`, and each character after it is darker, down to
+/// `from` scaled by `min_factor` at the last character (so 0.0 fades to black, and 1.0 doesn't
+/// fade at all). `min_factor` is clamped to the range 0.0–1.0, and NaN is treated as 1.0. A color
+/// code is only added where the color changes (see `map_chars`), and a single `RESET` is added at
+/// the end. An empty string is returned unchanged. Not supported on all terminals (see
+/// `truecolor`).
+///
+/// ```
+/// use flower_pot::*;
+///
+/// println!("{}", fade("...loading", (255, 255, 255), 0.2));
+/// assert_eq!(fade("abc", (200, 100, 0), 0.5), format!(
+///     "{}a{}b{}c{RESET}", truecolor(200, 100, 0), truecolor(150, 75, 0), truecolor(100, 50, 0),
+/// ));
+/// ```
+pub fn fade(text: &str, from: (u8, u8, u8), min_factor: f32) -> String {
+    let steps = text.chars().count();
+    let min_factor = if min_factor.is_nan() { 1.0 } else { min_factor.clamp(0.0, 1.0) };
+
+    map_chars(text, |i, _| {
+        let (r, g, b) = darken(from, (1.0 - min_factor) * fraction(i, steps));
+        Style::new().fg(Color::Rgb(r, g, b))
+    })
+}
+
+/// Apply `style` to each line of the text separately, with the style at the start of every line
+/// and a `RESET` at the end of every line (before its newline), so that each line is
+/// self-contained: nothing bleeds into the next line when the output is read line by line,
+/// wrapped, or cut up by another tool. Lines are split on `\n`. Empty lines are left empty, and a
+/// trailing newline is kept only if the text had one. Each line is styled the same way `styled`
+/// would style it, so while styling is turned off (see `set_colors_enabled`), only the text is
+/// returned.
+///
+/// ```
+/// use flower_pot::*;
+///
+/// let text = style_lines("first\n\nsecond\n", &Style::new().bold());
+/// assert_eq!(text, format!("{BOLD}first{RESET}\n\n{BOLD}second{RESET}\n"));
+/// ```
+pub fn style_lines(text: &str, style: &Style) -> String {
+    let mut out = String::new();
+    for (i, line) in text.split('\n').enumerate() {
+        if i > 0 {
+            out.push('\n');
+        }
+        if !line.is_empty() {
+            let _ = write!(out, "{}", Styled::new(line, style));
+        }
+    }
+    out
+}
+
+/// Give lines alternating background colors, for lists and reports that are easier to read with
+/// striped rows: the first line (line 0) and every other line after it get `even`, and the rest
+/// get `odd`. Each line is wrapped in its background color and a trailing `RESET`, so any styling
+/// already in a line is kept, but note that a `RESET` in the middle of a line also ends its
+/// background. The background only covers the line's text, so an empty line shows no background
+/// at all; pad the lines to the same width first (see `pad_visible`) for full-width stripes.
+///
+/// ```
+/// use flower_pot::*;
+///
+/// let lines = ["apples", "pears", "plums"].map(String::from);
+/// for line in zebra(lines, Color::Palette(236), Color::Palette(238)) {
+///     println!("{line}");
+/// }
+/// ```
+pub fn zebra<I: IntoIterator<Item = String>>(lines: I, even: Color, odd: Color) -> impl Iterator<Item = String> {
+    let (even, odd) = (Style::new().bg(even), Style::new().bg(odd));
+    lines
+        .into_iter()
+        .enumerate()
+        .map(move |(i, line)| format!("{}{line}{RESET}", if i % 2 == 0 { even } else { odd }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::*;
+
+    #[test]
+    fn gradient_endpoints() {
+        let expected = format!(
+            "{}a{}b{}c{RESET}",
+            truecolor(0, 0, 0),
+            truecolor(188, 71, 0),
+            truecolor(255, 100, 0),
+        );
+        assert_eq!(gradient("abc", (0, 0, 0), (255, 100, 0)), expected);
+    }
+
+    #[test]
+    fn gradient_short_inputs() {
+        assert_eq!(gradient("", (1, 2, 3), (4, 5, 6)), "");
+        assert_eq!(gradient("x", (1, 2, 3), (4, 5, 6)), format!("{}x{RESET}", truecolor(1, 2, 3)));
+    }
+
+    #[test]
+    fn gradient_256_steps() {
+        let (black, white) = (color_256_str(16), color_256_str(231));
+        let gray = color_256_str(rgb_to_256(188, 188, 188));
+        assert_eq!(gradient_256("abc", (0, 0, 0), (255, 255, 255)),    format!("{black}a{gray}b{white}c{RESET}"));
+        assert_eq!(gradient_256("abc", (0, 0, 0), (2, 2, 2)),          format!("{black}abc{RESET}"));
+        assert_eq!(gradient_256("", (1, 2, 3), (4, 5, 6)),             "");
+        assert_eq!(gradient_256_bg("ab", (0, 0, 0), (255, 255, 255)),  format!(
+            "{}a{}b{RESET}", color_256_bg_str(16), color_256_bg_str(231),
+        ));
+    }
+
+    #[test]
+    fn rainbow_hues() {
+        let expected = format!(
+            "{}a{}b{}ç{RESET}",
+            truecolor(255, 0, 0),
+            truecolor(0, 255, 0),
+            truecolor(0, 0, 255),
+        );
+        assert_eq!(rainbow("abç"), expected);
+        assert_eq!(rainbow(""), "");
+
+        let expected = format!("{}a{}b{RESET}", truecolor(0, 0, 255), truecolor(255, 255, 0));
+        assert_eq!(rainbow_with("ab", 240.0, 1.0, 0.5), expected);
+    }
+
+    #[test]
+    fn gradient_multibyte() {
+        let expected = format!("{}é{}ß{RESET}", truecolor_bg(9, 9, 9), truecolor_bg(0, 0, 0));
+        assert_eq!(gradient_bg("éß", (9, 9, 9), (0, 0, 0)), expected);
+    }
+
+    #[test]
+    fn map_chars_only_emits_changes() {
+        let red = Color::Named(NamedColor::Red);
+        let style = |i: usize, _| match i {
+            0 | 1   => Style::new().bold(),
+            2       => Style::new().bold().fg(red),
+            _       => Style::new(),
+        };
+
+        assert_eq!(map_chars("abcd", style),    format!("{BOLD}ab{RED}c{RESET}d"));
+        assert_eq!(map_chars("abc", style),     format!("{BOLD}ab{RED}c{RESET}"));
+        assert_eq!(map_chars("é", |_, c| Style::new().italic().when(c == 'é')), format!("{ITALIC}é{RESET}"));
+        assert_eq!(map_chars("ab", |_, _| Style::new()), "ab");
+        assert_eq!(map_chars("", style), "");
+    }
+
+    #[test]
+    fn zebra_alternates() {
+        let lines = ["a", "", &format!("{BOLD}c{RESET}")].map(|s| s.to_string());
+        let striped: Vec<String> = zebra(lines, Color::Named(NamedColor::Blue), Color::Palette(7)).collect();
+        assert_eq!(striped, [
+            format!("{BLUE_BG}a{RESET}"),
+            format!("{}{RESET}", color_256_bg(7)),
+            format!("{BLUE_BG}{BOLD}c{RESET}{RESET}"),
+        ]);
+    }
+
+    #[test]
+    fn fade_steps() {
+        let white = truecolor(255, 255, 255);
+        assert_eq!(fade("", (255, 255, 255), 0.0),          "");
+        assert_eq!(fade("x", (255, 255, 255), 0.0),         format!("{white}x{RESET}"));
+        assert_eq!(fade("xy", (255, 255, 255), 0.0),        format!("{white}x{}y{RESET}", truecolor(0, 0, 0)));
+        assert_eq!(fade("xyz", (255, 255, 255), 1.0),       format!("{white}xyz{RESET}"));
+        assert_eq!(fade("xyz", (255, 255, 255), f32::NAN),  format!("{white}xyz{RESET}"));
+        assert_eq!(fade("xy", (255, 255, 255), -3.0),       fade("xy", (255, 255, 255), 0.0));
+        assert_eq!(fade("é€", (100, 100, 100), 0.5),       format!("{}é{}€{RESET}", truecolor(100, 100, 100), truecolor(50, 50, 50)));
+
+        // Dark colors stop changing once the scaled channels round to the same values.
+        assert_eq!(fade("abcd", (2, 2, 2), 0.9), format!("{}abcd{RESET}", truecolor(2, 2, 2)));
+    }
+
+    #[test]
+    fn style_lines_are_self_contained() {
+        let red = Style::new().fg(Color::Named(NamedColor::Red));
+        assert_eq!(style_lines("a\nb", &red),          format!("{RED}a{RESET}\n{RED}b{RESET}"));
+        assert_eq!(style_lines("a\n\nb\n", &red),      format!("{RED}a{RESET}\n\n{RED}b{RESET}\n"));
+        assert_eq!(style_lines("\n", &red),            "\n");
+        assert_eq!(style_lines("", &red),              "");
+        assert_eq!(style_lines("a\nb", &Style::new()), "a\nb");
+    }
+}
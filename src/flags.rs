@@ -0,0 +1,347 @@
+// Text attributes and sets of them.
+
+use core::fmt;
+use core::ops::{BitAnd, BitOr, BitOrAssign, Sub};
+
+use crate::{
+    NEITHER_BOLD_NOR_ITALIC, NEITHER_FRAMED_NOR_ENCIRCLED, NORMAL_INTENSITY, NOT_BLINKING, NOT_HIDDEN,
+    NOT_INVERTED, NOT_OVERLINED, NOT_STRIKETHROUGH, NOT_UNDERLINED,
+};
+
+/// A set of on/off text attributes (bold, dim, italic, underline, blink, inverted, hidden,
+/// strikethrough, framed, encircled, and overline), stored as the bits of a single integer so that
+/// sets can be combined and compared cheaply.
+/// Combine the constants with `|`:
+///
+/// ```
+/// use flower_pot::*;
+///
+/// let flags = StyleFlags::BOLD | StyleFlags::UNDERLINE;
+/// assert!(flags.contains(StyleFlags::BOLD));
+/// assert_eq!(flags.enable().to_string(), "\x1b[1;4m");
+/// assert_eq!(flags.disable().to_string(), "\x1b[22;24m");
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct StyleFlags(u16);
+
+/// Each attribute, with the SGR parameter that turns it on and the one that turns it off (taken
+/// from `reset_for_attribute`, so that the two can't disagree).
+pub(crate) const FLAG_PARAMS: [(StyleFlags, u8, u8); 11] = [
+    (StyleFlags::BOLD,          1,  reset_param(Attribute::Bold)),
+    (StyleFlags::DIM,           2,  reset_param(Attribute::Dim)),
+    (StyleFlags::ITALIC,        3,  reset_param(Attribute::Italic)),
+    (StyleFlags::UNDERLINE,     4,  reset_param(Attribute::Underline)),
+    (StyleFlags::BLINK,         5,  reset_param(Attribute::Blink)),
+    (StyleFlags::INVERTED,      7,  reset_param(Attribute::Inverted)),
+    (StyleFlags::HIDDEN,        8,  reset_param(Attribute::Hidden)),
+    (StyleFlags::STRIKETHROUGH, 9,  reset_param(Attribute::Strikethrough)),
+    (StyleFlags::FRAMED,        51, reset_param(Attribute::Framed)),
+    (StyleFlags::ENCIRCLED,     52, reset_param(Attribute::Encircled)),
+    (StyleFlags::OVERLINE,      53, reset_param(Attribute::Overline)),
+];
+
+impl StyleFlags {
+    /// No attributes.
+    pub const EMPTY:            StyleFlags = StyleFlags(0);
+    /// Bold (see `BOLD`).
+    pub const BOLD:             StyleFlags = StyleFlags(1 << 0);
+    /// Dim (see `DIM`).
+    pub const DIM:              StyleFlags = StyleFlags(1 << 1);
+    /// Italic (see `ITALIC`).
+    pub const ITALIC:           StyleFlags = StyleFlags(1 << 2);
+    /// Underlined (see `UNDERLINE`).
+    pub const UNDERLINE:        StyleFlags = StyleFlags(1 << 3);
+    /// Blinking (see `SLOW_BLINK`).
+    pub const BLINK:            StyleFlags = StyleFlags(1 << 4);
+    /// Foreground and background colors swapped (see `INVERTED`).
+    pub const INVERTED:         StyleFlags = StyleFlags(1 << 5);
+    /// Hidden (see `HIDDEN`).
+    pub const HIDDEN:           StyleFlags = StyleFlags(1 << 6);
+    /// Strikethrough (see `STRIKETHROUGH`).
+    pub const STRIKETHROUGH:    StyleFlags = StyleFlags(1 << 7);
+    /// Framed (see `FRAMED`).
+    pub const FRAMED:           StyleFlags = StyleFlags(1 << 8);
+    /// Encircled (see `ENCIRCLED`).
+    pub const ENCIRCLED:        StyleFlags = StyleFlags(1 << 9);
+    /// Overlined (see `OVERLINE`).
+    pub const OVERLINE:         StyleFlags = StyleFlags(1 << 10);
+    /// All of the attributes.
+    pub const ALL:              StyleFlags = StyleFlags(0x7ff);
+
+    /// The set as an integer, with one bit per attribute (bold is bit 0, dim is bit 1, and so on in
+    /// the order of the constants above).
+    pub const fn bits(self) -> u16 {
+        self.0
+    }
+
+    /// The set with the given bits (see `bits`). Bits that don't stand for an attribute are ignored.
+    pub const fn from_bits(bits: u16) -> StyleFlags {
+        StyleFlags(bits & StyleFlags::ALL.0)
+    }
+
+    /// Whether the set has no attributes.
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Whether every attribute in `other` is also in this set.
+    pub const fn contains(self, other: StyleFlags) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// The attributes in either set (also available as `|`).
+    pub const fn union(self, other: StyleFlags) -> StyleFlags {
+        StyleFlags(self.0 | other.0)
+    }
+
+    /// The attributes in both sets (also available as `&`).
+    pub const fn intersection(self, other: StyleFlags) -> StyleFlags {
+        StyleFlags(self.0 & other.0)
+    }
+
+    /// The attributes in this set but not in `other` (also available as `-`).
+    pub const fn difference(self, other: StyleFlags) -> StyleFlags {
+        StyleFlags(self.0 & !other.0)
+    }
+
+    /// A control code that turns on every attribute in the set, such as `\x1b[1;4m` for bold and
+    /// underline. Displays as the empty string if the set is empty.
+    pub const fn enable(self) -> FlagsCode {
+        FlagsCode { flags: self, enable: true }
+    }
+
+    /// A control code that turns off every attribute in the set, such as `\x1b[22;24m` for bold and
+    /// underline. Bold and dim are turned off by the same parameter (22, `NORMAL_INTENSITY`), as
+    /// are framed and encircled (54, `NEITHER_FRAMED_NOR_ENCIRCLED`), so turning off either one of a
+    /// pair turns off both. Displays as the empty string if the set is empty.
+    pub const fn disable(self) -> FlagsCode {
+        FlagsCode { flags: self, enable: false }
+    }
+}
+
+impl BitOr for StyleFlags {
+    type Output = StyleFlags;
+
+    fn bitor(self, other: StyleFlags) -> StyleFlags {
+        self.union(other)
+    }
+}
+
+impl BitOrAssign for StyleFlags {
+    fn bitor_assign(&mut self, other: StyleFlags) {
+        *self = self.union(other);
+    }
+}
+
+impl BitAnd for StyleFlags {
+    type Output = StyleFlags;
+
+    fn bitand(self, other: StyleFlags) -> StyleFlags {
+        self.intersection(other)
+    }
+}
+
+impl Sub for StyleFlags {
+    type Output = StyleFlags;
+
+    fn sub(self, other: StyleFlags) -> StyleFlags {
+        self.difference(other)
+    }
+}
+
+/// The control code that turns a set of attributes on or off, as returned by `StyleFlags::enable`
+/// and `StyleFlags::disable`. Displays as the control code.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct FlagsCode {
+    flags:  StyleFlags,
+    enable: bool,
+}
+
+impl fmt::Display for FlagsCode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut last = None;
+        for (flag, on, off) in FLAG_PARAMS {
+            let param = if self.enable { on } else { off };
+            if self.flags.contains(flag) && last != Some(param) {
+                f.write_str(if last.is_none() { "\x1b[" } else { ";" })?;
+                write!(f, "{param}")?;
+                last = Some(param);
+            }
+        }
+
+        if last.is_some() {
+            f.write_str("m")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A text attribute that can be turned on and off, for looking up the control code that turns it
+/// off with `reset_for_attribute`. Unlike `StyleFlags`, this includes the attributes that a
+/// `Style` can't represent.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Attribute {
+    /// Bold (`BOLD`).
+    Bold,
+    /// Dim (`DIM`).
+    Dim,
+    /// Italic (`ITALIC`).
+    Italic,
+    /// The Fraktur font (`FRAKTUR`).
+    Fraktur,
+    /// Underlined (`UNDERLINE`).
+    Underline,
+    /// Double-underlined (`DOUBLE_UNDERLINE`).
+    DoubleUnderline,
+    /// Blinking (`SLOW_BLINK` or `RAPID_BLINK`).
+    Blink,
+    /// Foreground and background colors swapped (`INVERTED`).
+    Inverted,
+    /// Hidden (`HIDDEN`).
+    Hidden,
+    /// Strikethrough (`STRIKETHROUGH`).
+    Strikethrough,
+    /// Framed (`FRAMED`).
+    Framed,
+    /// Encircled (`ENCIRCLED`).
+    Encircled,
+    /// Overlined (`OVERLINE`).
+    Overline,
+}
+
+/// The control code that turns off `attribute`, without touching the colors or the other
+/// attributes, except that some codes turn off two attributes at once:
+///
+/// | Attribute                      | Control code                    |
+/// |--------------------------------|---------------------------------|
+/// | bold, dim                      | `NORMAL_INTENSITY`              |
+/// | italic, Fraktur                | `NEITHER_BOLD_NOR_ITALIC`       |
+/// | underline, double underline    | `NOT_UNDERLINED`                |
+/// | blink                          | `NOT_BLINKING`                  |
+/// | inverted                       | `NOT_INVERTED`                  |
+/// | hidden                         | `NOT_HIDDEN`                    |
+/// | strikethrough                  | `NOT_STRIKETHROUGH`             |
+/// | framed, encircled              | `NEITHER_FRAMED_NOR_ENCIRCLED`  |
+/// | overline                       | `NOT_OVERLINED`                 |
+///
+/// So turning off bold also turns off dim; if the text should stay dim, turn dim back on
+/// afterwards (which is what `Style::diff` does). Bold is turned off with `NORMAL_INTENSITY`
+/// rather than `NOT_BOLD`, because some terminals treat `NOT_BOLD` as a double underline.
+///
+/// ```
+/// use flower_pot::*;
+///
+/// assert_eq!(reset_for_attribute(Attribute::Underline), NOT_UNDERLINED);
+/// assert_eq!(reset_for_attribute(Attribute::Dim), NORMAL_INTENSITY);
+/// ```
+pub const fn reset_for_attribute(attribute: Attribute) -> &'static str {
+    match attribute {
+        Attribute::Bold | Attribute::Dim                  => NORMAL_INTENSITY,
+        Attribute::Italic | Attribute::Fraktur            => NEITHER_BOLD_NOR_ITALIC,
+        Attribute::Underline | Attribute::DoubleUnderline => NOT_UNDERLINED,
+        Attribute::Blink                                  => NOT_BLINKING,
+        Attribute::Inverted                               => NOT_INVERTED,
+        Attribute::Hidden                                 => NOT_HIDDEN,
+        Attribute::Strikethrough                          => NOT_STRIKETHROUGH,
+        Attribute::Framed | Attribute::Encircled          => NEITHER_FRAMED_NOR_ENCIRCLED,
+        Attribute::Overline                               => NOT_OVERLINED,
+    }
+}
+
+/// The SGR parameter of the control code that turns off `attribute` (see `reset_for_attribute`),
+/// such as 24 for `NOT_UNDERLINED`.
+pub(crate) const fn reset_param(attribute: Attribute) -> u8 {
+    let code = reset_for_attribute(attribute).as_bytes();
+    let mut param = 0;
+    let mut i = 2;
+    while code[i] != b'm' {
+        param = param * 10 + (code[i] - b'0');
+        i += 1;
+    }
+    param
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::*;
+
+    #[test]
+    fn single_flag_params() {
+        let expected = [
+            (StyleFlags::BOLD,          BOLD,           NORMAL_INTENSITY),
+            (StyleFlags::DIM,           DIM,            NORMAL_INTENSITY),
+            (StyleFlags::ITALIC,        ITALIC,         NEITHER_BOLD_NOR_ITALIC),
+            (StyleFlags::UNDERLINE,     UNDERLINE,      NOT_UNDERLINED),
+            (StyleFlags::BLINK,         SLOW_BLINK,     NOT_BLINKING),
+            (StyleFlags::INVERTED,      INVERTED,       NOT_INVERTED),
+            (StyleFlags::HIDDEN,        HIDDEN,         NOT_HIDDEN),
+            (StyleFlags::STRIKETHROUGH, STRIKETHROUGH,  NOT_STRIKETHROUGH),
+        ];
+
+        for (flag, on, off) in expected {
+            assert_eq!(flag.enable().to_string(), on);
+            assert_eq!(flag.disable().to_string(), off);
+        }
+    }
+
+    #[test]
+    fn combined_flags() {
+        assert_eq!(StyleFlags::EMPTY.enable().to_string(), "");
+        assert_eq!(StyleFlags::EMPTY.disable().to_string(), "");
+        assert_eq!(StyleFlags::ALL.enable().to_string(), "\x1b[1;2;3;4;5;7;8;9;51;52;53m");
+        assert_eq!(StyleFlags::ALL.disable().to_string(), "\x1b[22;23;24;25;27;28;29;54;55m");
+        assert_eq!((StyleFlags::FRAMED | StyleFlags::ENCIRCLED).disable().to_string(), NEITHER_FRAMED_NOR_ENCIRCLED);
+        assert_eq!((StyleFlags::BOLD | StyleFlags::DIM).disable().to_string(), NORMAL_INTENSITY);
+    }
+
+    #[test]
+    fn set_operations() {
+        let a = StyleFlags::BOLD | StyleFlags::ITALIC;
+        let b = StyleFlags::ITALIC | StyleFlags::HIDDEN;
+
+        assert_eq!(a & b, StyleFlags::ITALIC);
+        assert_eq!(a - b, StyleFlags::BOLD);
+        assert_eq!((a | b).bits(), 0b0100_0101);
+        assert_eq!(StyleFlags::from_bits(0b0100_0101), a | b);
+        assert_eq!(StyleFlags::from_bits(0xffff), StyleFlags::ALL);
+        assert!(a.contains(StyleFlags::BOLD));
+        assert!(!a.contains(b));
+        assert!(StyleFlags::default().is_empty());
+
+        let mut c = StyleFlags::EMPTY;
+        c |= StyleFlags::STRIKETHROUGH;
+        assert_eq!(c, StyleFlags::STRIKETHROUGH);
+    }
+
+    #[test]
+    fn attribute_resets_match_flag_params() {
+        let attributes = [
+            Attribute::Bold,
+            Attribute::Dim,
+            Attribute::Italic,
+            Attribute::Underline,
+            Attribute::Blink,
+            Attribute::Inverted,
+            Attribute::Hidden,
+            Attribute::Strikethrough,
+            Attribute::Framed,
+            Attribute::Encircled,
+            Attribute::Overline,
+        ];
+        for ((flag, _, off), attribute) in FLAG_PARAMS.into_iter().zip(attributes) {
+            assert_eq!(reset_for_attribute(attribute), flag.disable().to_string());
+            assert_eq!(reset_for_attribute(attribute), format!("\x1b[{off}m"));
+        }
+
+        for attribute in attributes.into_iter().chain([Attribute::Fraktur, Attribute::DoubleUnderline]) {
+            assert_eq!(format!("\x1b[{}m", reset_param(attribute)), reset_for_attribute(attribute));
+        }
+
+        assert_eq!(reset_for_attribute(Attribute::Fraktur),         NEITHER_BOLD_NOR_ITALIC);
+        assert_eq!(reset_for_attribute(Attribute::DoubleUnderline), NOT_UNDERLINED);
+        assert_eq!(reset_for_attribute(Attribute::Encircled),       NEITHER_FRAMED_NOR_ENCIRCLED);
+        assert_eq!(reset_for_attribute(Attribute::Overline),        NOT_OVERLINED);
+    }
+}
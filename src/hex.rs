@@ -0,0 +1,179 @@
+// Hex color strings (e.g. "#ff2d44") and packed hex values (e.g. 0xff2d44).
+
+use core::fmt;
+
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+
+#[cfg(feature = "alloc")]
+use crate::{truecolor, truecolor_bg};
+
+/// The error returned when a hex color string can't be parsed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParseHexError {
+    /// The string (not counting a leading `#`) had this many digits instead of 3 or 6.
+    InvalidLength(usize),
+    /// The string contained this character, which is not a hex digit.
+    InvalidDigit(char),
+}
+
+impl fmt::Display for ParseHexError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseHexError::InvalidLength(n) => write!(f, "hex color must have 3 or 6 digits, found {n}"),
+            ParseHexError::InvalidDigit(c)  => write!(f, "invalid hex digit {c:?} in hex color"),
+        }
+    }
+}
+
+impl core::error::Error for ParseHexError {}
+
+/// Parse a hex color string into an RGB triple. Accepts 6-digit (`#ff2d44`) and 3-digit shorthand
+/// (`#f2a`, which means `#ff22aa`) forms, with or without the leading `#`. Digits are
+/// case-insensitive.
+pub fn parse_hex(hex: &str) -> Result<(u8, u8, u8), ParseHexError> {
+    let digits = hex.strip_prefix('#').unwrap_or(hex);
+
+    let mut values = [0u8; 6];
+    let mut count = 0;
+
+    for c in digits.chars() {
+        let value = c.to_digit(16).ok_or(ParseHexError::InvalidDigit(c))? as u8;
+        if count < 6 {
+            values[count] = value;
+        }
+        count += 1;
+    }
+
+    match count {
+        3 => Ok((values[0] * 17, values[1] * 17, values[2] * 17)),
+        6 => Ok((
+            values[0] * 16 + values[1],
+            values[2] * 16 + values[3],
+            values[4] * 16 + values[5],
+        )),
+        n => Err(ParseHexError::InvalidLength(n)),
+    }
+}
+
+/// The value of the hex digit `c`, for `parse_hex_literal`.
+const fn hex_digit(c: u8) -> u8 {
+    match c {
+        b'0'..=b'9' => c - b'0',
+        b'a'..=b'f' => c - b'a' + 10,
+        b'A'..=b'F' => c - b'A' + 10,
+        _           => panic!("invalid hex digit in hex color"),
+    }
+}
+
+/// Like `parse_hex`, but usable in constants, where an invalid string is a compile-time error.
+/// Panics if the string is invalid.
+pub(crate) const fn parse_hex_literal(hex: &str) -> (u8, u8, u8) {
+    let d = match hex.as_bytes() {
+        [b'#', digits @ ..] => digits,
+        digits              => digits,
+    };
+
+    match d.len() {
+        3 => (hex_digit(d[0]) * 17, hex_digit(d[1]) * 17, hex_digit(d[2]) * 17),
+        6 => (
+            hex_digit(d[0]) * 16 + hex_digit(d[1]),
+            hex_digit(d[2]) * 16 + hex_digit(d[3]),
+            hex_digit(d[4]) * 16 + hex_digit(d[5]),
+        ),
+        _ => panic!("hex color must have 3 or 6 digits"),
+    }
+}
+
+/// Set the foreground color to the RGB value described by a hex string such as `#ff2d44` or `#f2a`.
+/// See `parse_hex` for the accepted forms. Not supported on all terminals (see `truecolor`).
+#[cfg(feature = "alloc")]
+pub fn truecolor_hex(hex: &str) -> Result<String, ParseHexError> {
+    let (r, g, b) = parse_hex(hex)?;
+    Ok(truecolor(r, g, b))
+}
+
+/// Set the background color to the RGB value described by a hex string such as `#ff2d44` or
+/// `#f2a`. See `parse_hex` for the accepted forms. Not supported on all terminals (see
+/// `truecolor_bg`).
+#[cfg(feature = "alloc")]
+pub fn truecolor_hex_bg(hex: &str) -> Result<String, ParseHexError> {
+    let (r, g, b) = parse_hex(hex)?;
+    Ok(truecolor_bg(r, g, b))
+}
+
+/// Unpack an RGB value packed into the low 24 bits of an integer as `0xRRGGBB`, such as
+/// `0xff2d44`. The high 8 bits (sometimes used for an alpha channel) are ignored.
+pub const fn rgb_from_u32(rgb: u32) -> (u8, u8, u8) {
+    ((rgb >> 16) as u8, (rgb >> 8) as u8, rgb as u8)
+}
+
+/// Pack an RGB value into an integer as `0xRRGGBB`, the reverse of `rgb_from_u32`.
+///
+/// ```
+/// use flower_pot::*;
+///
+/// assert_eq!(u32_from_rgb(0xff, 0x2d, 0x44), 0xff2d44);
+/// ```
+pub const fn u32_from_rgb(r: u8, g: u8, b: u8) -> u32 {
+    (r as u32) << 16 | (g as u32) << 8 | b as u32
+}
+
+/// Set the foreground color to the RGB value packed into the low 24 bits of `rgb` as `0xRRGGBB`,
+/// such as `0xff2d44`. The high 8 bits are ignored. Not supported on all terminals (see
+/// `truecolor`).
+#[cfg(feature = "alloc")]
+pub fn truecolor_from_u32(rgb: u32) -> String {
+    let (r, g, b) = rgb_from_u32(rgb);
+    truecolor(r, g, b)
+}
+
+/// Set the background color to the RGB value packed into the low 24 bits of `rgb` as `0xRRGGBB`,
+/// such as `0xff2d44`. The high 8 bits are ignored. Not supported on all terminals (see
+/// `truecolor_bg`).
+#[cfg(feature = "alloc")]
+pub fn truecolor_from_u32_bg(rgb: u32) -> String {
+    let (r, g, b) = rgb_from_u32(rgb);
+    truecolor_bg(r, g, b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn shorthand_expands() {
+        assert_eq!(parse_hex("#f2a"), Ok((0xff, 0x22, 0xaa)));
+        assert_eq!(parse_hex("#f2a"), parse_hex("#ff22aa"));
+        assert_eq!(truecolor_hex("f2a"), Ok(truecolor(0xff, 0x22, 0xaa)));
+        assert_eq!(truecolor_hex_bg("#ff2d44"), Ok(truecolor_bg(0xff, 0x2d, 0x44)));
+    }
+
+    #[test]
+    fn case_insensitive() {
+        assert_eq!(parse_hex("#FF2D44"), parse_hex("#ff2d44"));
+        assert_eq!(parse_hex("aBc"), Ok((0xaa, 0xbb, 0xcc)));
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(parse_hex("#ff2d4"),   Err(ParseHexError::InvalidLength(5)));
+        assert_eq!(parse_hex(""),         Err(ParseHexError::InvalidLength(0)));
+        assert_eq!(parse_hex("#ff2d4g"),  Err(ParseHexError::InvalidDigit('g')));
+        assert_eq!(parse_hex("##fff"),    Err(ParseHexError::InvalidDigit('#')));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn packed_values() {
+        assert_eq!(rgb_from_u32(0xff2d44),      (0xff, 0x2d, 0x44));
+        assert_eq!(rgb_from_u32(0x80ff2d44),    (0xff, 0x2d, 0x44));
+        assert_eq!(u32_from_rgb(0, 0, 0),       0);
+        assert_eq!(u32_from_rgb(1, 2, 3),       0x010203);
+        assert_eq!(rgb_from_u32(u32_from_rgb(9, 200, 31)), (9, 200, 31));
+
+        assert_eq!(truecolor_from_u32(0xff2d44),        truecolor(0xff, 0x2d, 0x44));
+        assert_eq!(truecolor_from_u32_bg(0xcc000001),   truecolor_bg(0, 0, 1));
+    }
+}
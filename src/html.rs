@@ -0,0 +1,136 @@
+// Converting styled strings to HTML.
+
+use core::fmt::{self, Write};
+
+use alloc::string::String;
+
+use crate::{palette_to_rgb, spans, Color, Style, StyleFlags};
+
+/// The CSS color for `color`, as `rgb(r, g, b)`. Named and palette colors use the values given by
+/// `palette_to_rgb`.
+fn write_css_color(out: &mut impl Write, color: Color) -> fmt::Result {
+    let (r, g, b) = match color {
+        Color::Named(c)     => c.to_rgb(),
+        Color::Palette(n)   => palette_to_rgb(n),
+        Color::Rgb(r, g, b) => (r, g, b),
+    };
+    write!(out, "rgb({r}, {g}, {b})")
+}
+
+/// Write the CSS declarations for `style`, separated by `; `. Writes nothing if none of the parts
+/// of the style that `to_html` translates are set.
+pub(crate) fn write_css<W: Write>(out: &mut W, style: &Style) -> fmt::Result {
+    let mut separator = "";
+    let mut declare = |out: &mut W, property: &str| -> fmt::Result {
+        write!(out, "{separator}{property}: ")?;
+        separator = "; ";
+        Ok(())
+    };
+
+    if let Some(color) = style.fg {
+        declare(out, "color")?;
+        write_css_color(out, color)?;
+    }
+    if let Some(color) = style.bg {
+        declare(out, "background-color")?;
+        write_css_color(out, color)?;
+    }
+    if style.flags().contains(StyleFlags::BOLD) {
+        declare(out, "font-weight")?;
+        out.write_str("bold")?;
+    }
+    if style.flags().contains(StyleFlags::ITALIC) {
+        declare(out, "font-style")?;
+        out.write_str("italic")?;
+    }
+    if style.flags().contains(StyleFlags::UNDERLINE) {
+        declare(out, "text-decoration")?;
+        out.write_str("underline")?;
+    }
+    Ok(())
+}
+
+/// Append `text` to `out` with the characters that are special in HTML (`&`, `<`, `>`, `"`, and
+/// `'`) replaced by character references.
+fn push_escaped(out: &mut String, text: &str) {
+    for c in text.chars() {
+        match c {
+            '&'     => out.push_str("&amp;"),
+            '<'     => out.push_str("&lt;"),
+            '>'     => out.push_str("&gt;"),
+            '"'     => out.push_str("&quot;"),
+            '\''    => out.push_str("&#39;"),
+            _       => out.push(c),
+        }
+    }
+}
+
+/// Convert a string containing SGR control codes into HTML, for showing terminal output (such as
+/// log snippets) in a web page. The string is split into runs of text with `spans`, and each run
+/// whose style sets any of the parts below is wrapped in a `<span style="...">` that translates
+/// them into CSS:
+///
+/// - The foreground color becomes `color`, and the background color becomes `background-color`.
+///   Both are written as `rgb(r, g, b)`. Named colors and palette colors use the xterm defaults
+///   given by `NamedColor::to_rgb` and `palette_to_rgb`, so `RED` becomes `rgb(205, 0, 0)`.
+/// - Bold becomes `font-weight: bold`, italic becomes `font-style: italic`, and underline becomes
+///   `text-decoration: underline`.
+///
+/// Other attributes (such as blinking) and control codes other than SGR codes are left out. The
+/// text is HTML-escaped, and every span is closed before the style changes, so the result never
+/// has unclosed tags, even if the input doesn't end with a `RESET`.
+///
+/// ```
+/// use flower_pot::*;
+///
+/// let html = to_html(&format!("{BOLD}a < b{RESET} or {}c", truecolor(255, 128, 0)));
+/// assert_eq!(html, concat!(
+///     r#"<span style="font-weight: bold">a &lt; b</span> or "#,
+///     r#"<span style="color: rgb(255, 128, 0)">c</span>"#,
+/// ));
+/// ```
+pub fn to_html(input: &str) -> String {
+    let mut out = String::new();
+    let mut css = String::new();
+
+    for span in spans(input) {
+        css.clear();
+        let _ = write_css(&mut css, &span.style);
+
+        if css.is_empty() {
+            push_escaped(&mut out, &span.text);
+        } else {
+            out.push_str("<span style=\"");
+            out.push_str(&css);
+            out.push_str("\">");
+            push_escaped(&mut out, &span.text);
+            out.push_str("</span>");
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::*;
+
+    #[test]
+    fn html_styles() {
+        let input = format!("{RED}{BLUE_BG}x{BOLD}{ITALIC}{UNDERLINE}y{RESET}{}z", color_256(196));
+        assert_eq!(to_html(&input), concat!(
+            r#"<span style="color: rgb(205, 0, 0); background-color: rgb(0, 0, 238)">x</span>"#,
+            r#"<span style="color: rgb(205, 0, 0); background-color: rgb(0, 0, 238); "#,
+            r#"font-weight: bold; font-style: italic; text-decoration: underline">y</span>"#,
+            r#"<span style="color: rgb(255, 0, 0)">z</span>"#,
+        ));
+    }
+
+    #[test]
+    fn html_escapes_and_skips_unknown() {
+        assert_eq!(to_html(r#"<a href="x">'&'</a>"#), "&lt;a href=&quot;x&quot;&gt;&#39;&amp;&#39;&lt;/a&gt;");
+        assert_eq!(to_html(&format!("{SLOW_BLINK}a{RESET}{}", hyperlink("url", "b"))), "ab");
+        assert_eq!(to_html(""), "");
+    }
+}
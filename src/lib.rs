@@ -1,7 +1,12 @@
 
+// The tests use the standard library (for `format!` and such) even without the `std` feature.
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 #![warn(missing_docs)]
+#![allow(clippy::redundant_static_lifetimes)]
+// Some internal helpers are only used by the functions that need the `alloc` feature.
+#![cfg_attr(not(feature = "alloc"), allow(dead_code))]
 
-//! Constants and simple functions for invoking ANSI control codes used for text-styling in terminals (including color codes). No support for cursor movement or any other control codes.
+//! Constants and simple functions for invoking ANSI control codes used for text-styling in terminals (including color codes). No support for cursor movement or any other control codes, apart from clickable hyperlinks (see `hyperlink`) and, with the `window-title` feature, window titles (see `window_title`).
 //!
 //! This crate provides constant bindings for text-styling ANSI control codes like `BOLD` (bound to the string `\x1b[1m`) and `GREEN` (bound to `\x1b[32m`):
 //!
@@ -19,6 +24,7 @@
 //!
 //! ```
 //! use flower_pot::*;
+//! # #[cfg(feature = "alloc")] {
 //!
 //! // Prints text in "palette-color #237" (often a shade of grey)
 //! // with a background color of "palette-color #214" (often a
@@ -26,21 +32,55 @@
 //!
 //! println!("{}{}example text{RESET}", color_256(237), color_256_bg(214));
 //!
+//! # }
 //! ```
 //!
 //! And functions that invoke truecolor functionality for terminals that support it:
 //!
 //! ```
 //! use flower_pot::*;
+//! # #[cfg(feature = "alloc")] {
 //!
 //! // Prints text in RGB color [127, 45, 68] with a background
 //! // color of RGB color [0, 255, 255]:
 //!
 //! println!("{}{}example text{RESET}", truecolor(127, 45, 68), truecolor_bg(0, 255, 255));
 //!
+//! # }
 //! ```
 //!
-//! The functions `color_256`, `color_256_bg`, `truecolor`, and `truecolor_bg` all return Strings.
+//! The functions `color_256`, `color_256_bg`, `truecolor`, and `truecolor_bg` all return Strings. If you'd rather not allocate, `color_256_code`, `color_256_bg_code`, `truecolor_code`, and `truecolor_bg_code` return a small `Copy` value that prints the same control code when displayed. If your colors come as hex strings like `#ff2d44`, `truecolor_hex` and `truecolor_hex_bg` will parse them for you. For terminals without truecolor support, `rgb_to_256` finds the nearest color in the 256-color palette.
+//!
+//! If you'd rather not keep track of `RESET` yourself, the `styled` method wraps any displayable value in a style and resets it afterwards. Styles can be the constants above or a `Style` built from several attributes and colors:
+//!
+//! ```
+//! use flower_pot::*;
+//!
+//! let alert = Style::new().bold().fg(Color::Named(NamedColor::Red));
+//!
+//! println!("{}", "ok".styled(GREEN));        // prints a green "ok"
+//! println!("{}", "error!".styled(alert));    // prints a bold, red "error!"
+//! ```
+//!
+//! When your program's output isn't going to a terminal (for example, when it's redirected into a file), you usually don't want any control codes in it. Call `auto_configure` at the start of your program to turn styling off in that case, or when the user has asked for no color with the `NO_COLOR` environment variable. This affects the `styled` method and the styling macros, but not the constants, which are always just control codes. You can also turn colors and text attributes on and off yourself, independently of each other, with `set_colors_enabled` and `set_attributes_enabled`. On Windows, also call `enable_vt_processing`, which turns on support for control codes in consoles that need it.
+//!
+//! These switches, and the others in this crate such as `set_background`, are global to the process and can be read and changed from any thread. Each one is a single atomic value, so a thread that reads a switch while another thread changes it sees either the old value or the new one, never anything in between. Beyond that, the switches are independent of each other and of the rest of memory (they use relaxed atomic ordering): a thread that sees the change to one switch isn't guaranteed to see a change made to another switch just before it. The setters are meant for configuration at startup, before your program starts other threads (anything a thread does before spawning another thread is visible to the new thread). Changing a switch while other threads are printing is safe, but those threads pick up the change at some unpredictable point in their output.
+//!
+//! This crate supports `no_std` environments. By default, the `std` feature is enabled, which in turn enables the `alloc` feature. The functions that return a `String` (or a `Vec`), including `color_256`, `color_256_bg`, `truecolor`, and `truecolor_bg`, are only available when the `alloc` feature is enabled. Everything else, including all of the constants, works without either feature. Without `alloc`, you can write 256-color and truecolor codes into any `core::fmt::Write` (such as a UART driver) using `ColorCode` or the `_into` functions such as `truecolor_into`:
+//!
+//! ```
+//! use core::fmt::Write;
+//! use flower_pot::*;
+//!
+//! fn status(out: &mut impl Write) -> core::fmt::Result {
+//!     write!(out, "{}{BOLD}ready{RESET}", truecolor_code(0, 255, 0))
+//! }
+//! # status(&mut String::new()).unwrap();
+//! ```
+//!
+//! The optional `window-title` feature adds `window_title`, for setting the title of the terminal window. It's off by default, because it does more than style text.
+//!
+//! The optional `serde` feature implements `Serialize` and `Deserialize` for `Color` and `NamedColor`, so that colors can be read from configuration files. Colors are written as names (`"red"`), hex strings (`"#ff2d44"`), or palette entries (`{ "palette": 214 }`).
 //!
 //! Note that not all terminals support all of the codes defined in this library. The basic workflow of ANSI control codes is that a program outputs sequences of special characters describing what it wants (such as "make the following text bold" or "make the following text green") to stdout, and then the terminal that the program is running in decides what to do with those characters. The codes themselves are reasonably well-standardized, but not every terminal understands all of them. Some terminals might ignore some codes, or might do weird things when you use them (such as displaying the text following the code incorrectly). This is a feature of the ANSI control code ecosystem, and not something a library can fix.
 //!
@@ -50,6 +90,87 @@
 //!
 //! The named colors covered by ANSI control codes are `BLACK`, `RED`, `GREEN`, `YELLOW`, `BLUE`, `MAGENTA`, `CYAN`, and `WHITE`, and these are available as foreground and background colors, along with "bright" versions which are also available as foreground and background colors. If you want shades of gray, you can try 8-bit colors in the range of **232** (darkest grey) to **255** (lightest grey) or use truecolor. Among the text-styling codes, I find `BOLD`, `DIM`, `ITALIC`, `UNDERLINE`, and `RESET` to be the most commonly useful.
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(feature = "alloc")]
+use alloc::{format, string::String};
+
+#[macro_use]
+mod macros;
+
+mod code;
+mod color;
+mod config;
+#[cfg(feature = "std")]
+mod console;
+#[cfg(feature = "std")]
+mod demo;
+#[cfg(feature = "alloc")]
+mod effects;
+mod flags;
+mod hex;
+#[cfg(feature = "alloc")]
+mod html;
+#[cfg(feature = "alloc")]
+mod osc;
+mod palette;
+mod rgb;
+mod scan;
+#[cfg(feature = "serde")]
+mod serde_impls;
+mod sgr;
+mod style;
+mod text;
+mod theme;
+mod writer;
+
+pub use code::*;
+pub use color::*;
+pub use config::*;
+#[cfg(feature = "std")]
+pub use console::*;
+#[cfg(feature = "std")]
+pub use demo::*;
+#[cfg(feature = "alloc")]
+pub use effects::*;
+pub use flags::*;
+pub use hex::*;
+#[cfg(feature = "alloc")]
+pub use html::*;
+#[cfg(feature = "alloc")]
+pub use osc::*;
+pub use palette::*;
+pub use rgb::*;
+pub use sgr::*;
+pub use style::*;
+pub use text::*;
+pub use theme::*;
+pub use writer::*;
+
+#[doc(hidden)]
+pub mod __private {
+    #[cfg(feature = "alloc")]
+    pub use alloc::string::String;
+
+    /// Write `style` for the `style!` macro (see `config::write_gated`).
+    #[cfg(feature = "alloc")]
+    pub fn write_gated(out: &mut String, style: impl core::fmt::Display) -> bool {
+        crate::config::write_gated(out, style).unwrap_or(false)
+    }
+
+    /// Write `RESET` for the `style!` macro (see `config::write_reset`).
+    #[cfg(feature = "alloc")]
+    pub fn write_reset(out: &mut String) {
+        let _ = crate::config::write_reset(out);
+    }
+
+    /// Parse the literal given to the `hex!` macro (see `hex::parse_hex_literal`).
+    pub const fn parse_hex_literal(hex: &str) -> (u8, u8, u8) {
+        crate::hex::parse_hex_literal(hex)
+    }
+}
+
 // Styles: 0-29.
 
 /// Unset all styles and return to default text formatting.
@@ -122,6 +243,24 @@ pub const NOT_HIDDEN:               &'static str = "\x1b[28m";
 /// Make the following text not strikethrough.
 pub const NOT_STRIKETHROUGH:        &'static str = "\x1b[29m";
 
+/// Undo the text-styling attributes for the following text while keeping the current foreground
+/// and background colors. The returned control code is `\x1b[22;23;24;25;27;28;29m`, which
+/// combines these SGR parameters:
+///
+/// - 22 (`NORMAL_INTENSITY`): neither bold nor dim.
+/// - 23 (`NEITHER_BOLD_NOR_ITALIC`): not italic (and not Fraktur).
+/// - 24 (`NOT_UNDERLINED`): neither underlined nor double-underlined.
+/// - 25 (`NOT_BLINKING`): not blinking.
+/// - 27 (`NOT_INVERTED`): foreground and background not swapped.
+/// - 28 (`NOT_HIDDEN`): not hidden.
+/// - 29 (`NOT_STRIKETHROUGH`): not strikethrough.
+///
+/// Fonts (10–20), proportional spacing (26), and framing, encircling, and overlining (51–55) are
+/// left alone.
+pub const fn reset_attributes() -> &'static str {
+    "\x1b[22;23;24;25;27;28;29m"
+}
+
 /// Set forground color to black for the following text.
 pub const BLACK:                    &'static str = "\x1b[30m";
 /// Set foreground color to red for the following text.
@@ -140,13 +279,13 @@ pub const CYAN:                     &'static str = "\x1b[36m";
 pub const WHITE:                    &'static str = "\x1b[37m";
 
 /// Set the foreground color for the following text to the *n*th color in the 256-color palette. Commonly, the set of 256 available colors consists of the 8 named foreground colors, the 8 bright versions of these colors, a 6×6×6 RGB cube (for a total of 216 colors distributed evenly across RGB-space), and then a scale of 24 shades of gray. Different terminals may differ in what colors they provide here.
-
+#[cfg(feature = "alloc")]
 pub fn color_256(n: u8) -> String {
     format!("\x1b[38;5;{n}m")
 }
 
 /// Set the foreground color to the RGB value (r, g, b). Not supported on all terminals. Terminals which do support this feature are called "truecolor terminals".
-
+#[cfg(feature = "alloc")]
 pub fn truecolor(r: u8, g: u8, b: u8) -> String {
     format!("\x1b[38;2;{r};{g};{b}m")
 }
@@ -172,13 +311,13 @@ pub const CYAN_BG:                  &'static str = "\x1b[46m";
 pub const WHITE_BG:                 &'static str = "\x1b[47m";
 
 /// Set the background color for the following text to the *n*th color in the 256-color palette. Commonly, the set of 256 available colors consists of the 8 named background colors, the 8 bright versions of these colors, a 6×6×6 RGB cube (for a total of 216 colors distributed evenly across RGB-space), and then a scale of 24 shades of gray. Different terminals may differ in what colors they provide here.
-
+#[cfg(feature = "alloc")]
 pub fn color_256_bg(n: u8) -> String {
     format!("\x1b[48;5;{n}m")
 }
 
 /// Set the background color to the RGB value (r, g, b). Not supported on all terminals. Terminals which do support this feature are called "truecolor terminals".
-
+#[cfg(feature = "alloc")]
 pub fn truecolor_bg(r: u8, g: u8, b: u8) -> String {
     format!("\x1b[48;2;{r};{g};{b}m")
 }
@@ -186,6 +325,14 @@ pub fn truecolor_bg(r: u8, g: u8, b: u8) -> String {
 /// Return to the default background color for the following text.
 pub const DEFAULT_BG:               &'static str = "\x1b[49m";
 
+/// Return to the default foreground and background colors for the following text while keeping
+/// all other styling. The returned control code is `\x1b[39;49m`, which combines the SGR parameters
+/// 39 (`DEFAULT`, the default foreground color) and 49 (`DEFAULT_BG`, the default background
+/// color). The underline color (59) is left alone.
+pub const fn reset_colors() -> &'static str {
+    "\x1b[39;49m"
+}
+
 /// Return to a non-proportionally spaced font for the following text. Rarely meaningful because
 /// the `PROPORTIONAL_SPACING` control code is rarely supported to begin with.
 pub const NO_PROPORTIONAL_SPACING:  &'static str = "\x1b[50m";
@@ -201,6 +348,26 @@ pub const NEITHER_FRAMED_NOR_ENCIRCLED:
 /// Make the following text not overlined.
 pub const NOT_OVERLINED:            &'static str = "\x1b[55m";
 
+/// Set the color of underlines for the following text to the *n*th color in the 256-color palette
+/// (see `color_256`), leaving the color of the text itself alone. Not widely supported: terminals
+/// that don't support it draw underlines in the text's color.
+#[cfg(feature = "alloc")]
+pub fn underline_color_256(n: u8) -> String {
+    format!("\x1b[58;5;{n}m")
+}
+
+/// Set the color of underlines for the following text to the RGB value (r, g, b), leaving the
+/// color of the text itself alone. Not widely supported: terminals that don't support it draw
+/// underlines in the text's color.
+#[cfg(feature = "alloc")]
+pub fn underline_color(r: u8, g: u8, b: u8) -> String {
+    format!("\x1b[58;2;{r};{g};{b}m")
+}
+
+/// Return to the default underline color (the color of the text) for the following text. Not
+/// widely supported.
+pub const DEFAULT_UNDERLINE_COLOR:  &'static str = "\x1b[59m";
+
 /// Set the foreground color to bright black for the following text.
 pub const BRIGHT_BLACK:             &'static str = "\x1b[90m";
 /// Set the foreground color to bright red for the following text.
@@ -235,12 +402,33 @@ pub const BRIGHT_CYAN_BG:           &'static str = "\x1b[106m";
 /// Set the background color to bright white for the following text.
 pub const BRIGHT_WHITE_BG:          &'static str = "\x1b[107m";
 
-// Test (requires manual inspection of outputs).
+// Tests.
 
-#[cfg(test)]
+#[cfg(all(test, feature = "alloc"))]
 mod tests {
     use super::*;
 
+    #[test]
+    fn reset_groups() {
+        use SgrParam::*;
+
+        assert_eq!(
+            parse_sgr(reset_attributes()).unwrap(),
+            [NormalIntensity, NotItalic, NotUnderlined, NotBlinking, NotInverted, NotHidden, NotStrikethrough],
+        );
+        assert_eq!(parse_sgr(reset_colors()).unwrap(), [DefaultForeground, DefaultBackground]);
+    }
+
+    #[test]
+    fn underline_colors() {
+        assert_eq!(parse_sgr(&underline_color(1, 2, 3)).unwrap(),    [SgrParam::UnderlineColor(Color::Rgb(1, 2, 3))]);
+        assert_eq!(parse_sgr(&underline_color_256(214)).unwrap(),    [SgrParam::UnderlineColor(Color::Palette(214))]);
+        assert_eq!(parse_sgr(DEFAULT_UNDERLINE_COLOR).unwrap(),      [SgrParam::DefaultUnderlineColor]);
+    }
+
+    // Test (requires manual inspection of outputs).
+
+    #[cfg(feature = "std")]
     #[test]
     fn print_and_verify_visually() {
         println!();
@@ -253,6 +441,8 @@ mod tests {
         println!("normal {ENCIRCLED}encircled{RESET}");
         println!("normal {FRAMED}framed{RESET}");
         println!("normal {OVERLINE}overline{RESET}");
+        println!("normal {UNDERLINE}{}red underline{DEFAULT_UNDERLINE_COLOR} default underline{RESET}", underline_color(255, 0, 0));
+        println!("normal {UNDERLINE}{}orange underline{RESET}", underline_color_256(214));
 
         println!(
             "{}g{}r{}e{}y{}s{}c{}a{}l{}e{} {}c{}o{}l{}o{}r{}s{}",
@@ -321,6 +511,7 @@ mod tests {
         println!("hidden: {HIDDEN}hidden{NOT_HIDDEN} revealed");
         println!("{}green fg {}reset fg", truecolor(0, 255, 0),    DEFAULT);
         println!("{}green bg {}reset bg", truecolor_bg(0, 255, 0), DEFAULT_BG);
+        println!("{BOLD}{ITALIC}{GREEN}styled {}colors kept {}all gone", reset_attributes(), reset_colors());
         println!();
 
         println!("{GREEN}ok{RESET}");           // prints a green "ok"
@@ -341,6 +532,9 @@ mod tests {
         println!("{DEFAULT_FONT}default font{RESET}");
         println!("{INVERTED}{GREEN}green fg but it's bg{RESET}");
         println!();
+
+        demo(&mut std::io::stdout()).unwrap();
+        println!();
     }
 }
 
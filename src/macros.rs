@@ -0,0 +1,235 @@
+// Macros for styling text inline.
+
+/// Format some text like `format!`, wrapped in the given styles and a trailing `RESET`. The styles
+/// go before the semicolon and are the names of this crate's constants in lowercase (`bold`,
+/// `red`, `bright_blue_bg`, and so on). Palette and truecolor colors can be given as
+/// `fg(256: n)`, `bg(256: n)`, `fg(rgb: r, g, b)`, and `bg(rgb: r, g, b)`. While colors or text
+/// attributes are turned off (see `set_colors_enabled` and `set_attributes_enabled`), those styles
+/// are left out, and if none are left, only the formatted text is returned.
+///
+/// ```
+/// use flower_pot::*;
+///
+/// let name = "world";
+/// let greeting = style!(bold, red; "hello, {}!", name);
+/// assert_eq!(greeting, format!("{BOLD}{RED}hello, world!{RESET}"));
+///
+/// let orange = style!(fg(256: 214), bg(rgb: 0, 0, 0); "{}", 42);
+/// assert_eq!(orange, format!("{}{}42{RESET}", color_256(214), truecolor_bg(0, 0, 0)));
+/// ```
+///
+/// Unknown style names are rejected at compile time:
+///
+/// ```compile_fail
+/// use flower_pot::*;
+///
+/// let oops = style!(bold, chartreuse; "text");
+/// ```
+#[cfg(feature = "alloc")]
+#[macro_export]
+macro_rules! style {
+    ($($name:ident $(($($args:tt)*))?),+ ; $($fmt:tt)+) => {{
+        use ::core::fmt::Write as _;
+        let mut out = $crate::__private::String::new();
+        let mut styled = false;
+        $(
+            styled |= $crate::__private::write_gated(&mut out, $crate::__style_code!($name $(($($args)*))?));
+        )+
+        let _ = ::core::write!(out, $($fmt)+);
+        if styled {
+            $crate::__private::write_reset(&mut out);
+        }
+        out
+    }};
+}
+
+/// Print some text to stdout like `println!`, wrapped in the given styles and a trailing `RESET`.
+/// The styles are given the same way as for `style!`, and the `RESET` comes before the newline.
+///
+/// ```
+/// use flower_pot::*;
+///
+/// let msg = "file not found";
+/// println_styled!(red, bold; "error: {}", msg);
+/// ```
+#[cfg(feature = "std")]
+#[macro_export]
+macro_rules! println_styled {
+    ($($args:tt)+) => {
+        ::std::println!("{}", $crate::style!($($args)+))
+    };
+}
+
+/// Print some text to stderr like `eprintln!`, wrapped in the given styles and a trailing `RESET`.
+/// The styles are given the same way as for `style!`, and the `RESET` comes before the newline.
+///
+/// ```
+/// use flower_pot::*;
+///
+/// let msg = "file not found";
+/// eprintln_styled!(red, bold; "error: {}", msg);
+/// ```
+#[cfg(feature = "std")]
+#[macro_export]
+macro_rules! eprintln_styled {
+    ($($args:tt)+) => {
+        ::std::eprintln!("{}", $crate::style!($($args)+))
+    };
+}
+
+/// A `Color::Rgb` from a hex color literal such as `"#ff2d44"` or `"#f2a"` (see `parse_hex` for the
+/// accepted forms), parsed at compile time, so the color can be used in constants and a typo is
+/// a compile error instead of a runtime one.
+///
+/// ```
+/// use flower_pot::*;
+///
+/// const BRAND: Style = Style::new().bold().fg(hex!("#ff2d44"));
+/// assert_eq!(hex!("#ff2d44"), Color::Rgb(0xff, 0x2d, 0x44));
+/// assert_eq!(hex!("F2A"), Color::Rgb(0xff, 0x22, 0xaa));
+/// assert_eq!(BRAND.to_string(), "\x1b[1;38;2;255;45;68m");
+/// ```
+///
+/// Invalid digits and lengths are rejected at compile time:
+///
+/// ```compile_fail
+/// let oops = flower_pot::hex!("#ff2d4g");
+/// ```
+///
+/// ```compile_fail
+/// let oops = flower_pot::hex!("#ff2d4");
+/// ```
+///
+/// ```compile_fail
+/// let oops = flower_pot::hex!(0xff2d44);
+/// ```
+#[macro_export]
+macro_rules! hex {
+    ($hex:literal) => {{
+        const RGB: (u8, u8, u8) = $crate::__private::parse_hex_literal($hex);
+        $crate::Color::Rgb(RGB.0, RGB.1, RGB.2)
+    }};
+}
+
+/// Map a style name from `style!` onto the corresponding control code.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __style_code {
+    (bold)                          => { $crate::BOLD };
+    (dim)                           => { $crate::DIM };
+    (italic)                        => { $crate::ITALIC };
+    (underline)                     => { $crate::UNDERLINE };
+    (slow_blink)                    => { $crate::SLOW_BLINK };
+    (rapid_blink)                   => { $crate::RAPID_BLINK };
+    (inverted)                      => { $crate::INVERTED };
+    (hidden)                        => { $crate::HIDDEN };
+    (strikethrough)                 => { $crate::STRIKETHROUGH };
+    (default_font)                  => { $crate::DEFAULT_FONT };
+    (alt_font_1)                    => { $crate::ALT_FONT_1 };
+    (alt_font_2)                    => { $crate::ALT_FONT_2 };
+    (alt_font_3)                    => { $crate::ALT_FONT_3 };
+    (alt_font_4)                    => { $crate::ALT_FONT_4 };
+    (alt_font_5)                    => { $crate::ALT_FONT_5 };
+    (alt_font_6)                    => { $crate::ALT_FONT_6 };
+    (alt_font_7)                    => { $crate::ALT_FONT_7 };
+    (alt_font_8)                    => { $crate::ALT_FONT_8 };
+    (alt_font_9)                    => { $crate::ALT_FONT_9 };
+    (fraktur)                       => { $crate::FRAKTUR };
+    (double_underline)              => { $crate::DOUBLE_UNDERLINE };
+    (not_bold)                      => { $crate::NOT_BOLD };
+    (normal_intensity)              => { $crate::NORMAL_INTENSITY };
+    (neither_bold_nor_italic)       => { $crate::NEITHER_BOLD_NOR_ITALIC };
+    (not_underlined)                => { $crate::NOT_UNDERLINED };
+    (not_blinking)                  => { $crate::NOT_BLINKING };
+    (proportional_spacing)          => { $crate::PROPORTIONAL_SPACING };
+    (not_inverted)                  => { $crate::NOT_INVERTED };
+    (not_hidden)                    => { $crate::NOT_HIDDEN };
+    (not_strikethrough)             => { $crate::NOT_STRIKETHROUGH };
+    (black)                         => { $crate::BLACK };
+    (red)                           => { $crate::RED };
+    (green)                         => { $crate::GREEN };
+    (yellow)                        => { $crate::YELLOW };
+    (blue)                          => { $crate::BLUE };
+    (magenta)                       => { $crate::MAGENTA };
+    (cyan)                          => { $crate::CYAN };
+    (white)                         => { $crate::WHITE };
+    (default)                       => { $crate::DEFAULT };
+    (black_bg)                      => { $crate::BLACK_BG };
+    (red_bg)                        => { $crate::RED_BG };
+    (green_bg)                      => { $crate::GREEN_BG };
+    (yellow_bg)                     => { $crate::YELLOW_BG };
+    (blue_bg)                       => { $crate::BLUE_BG };
+    (magenta_bg)                    => { $crate::MAGENTA_BG };
+    (cyan_bg)                       => { $crate::CYAN_BG };
+    (white_bg)                      => { $crate::WHITE_BG };
+    (default_bg)                    => { $crate::DEFAULT_BG };
+    (no_proportional_spacing)       => { $crate::NO_PROPORTIONAL_SPACING };
+    (framed)                        => { $crate::FRAMED };
+    (encircled)                     => { $crate::ENCIRCLED };
+    (overline)                      => { $crate::OVERLINE };
+    (neither_framed_nor_encircled)  => { $crate::NEITHER_FRAMED_NOR_ENCIRCLED };
+    (not_overlined)                 => { $crate::NOT_OVERLINED };
+    (bright_black)                  => { $crate::BRIGHT_BLACK };
+    (bright_red)                    => { $crate::BRIGHT_RED };
+    (bright_green)                  => { $crate::BRIGHT_GREEN };
+    (bright_yellow)                 => { $crate::BRIGHT_YELLOW };
+    (bright_blue)                   => { $crate::BRIGHT_BLUE };
+    (bright_magenta)                => { $crate::BRIGHT_MAGENTA };
+    (bright_cyan)                   => { $crate::BRIGHT_CYAN };
+    (bright_white)                  => { $crate::BRIGHT_WHITE };
+    (bright_black_bg)               => { $crate::BRIGHT_BLACK_BG };
+    (bright_red_bg)                 => { $crate::BRIGHT_RED_BG };
+    (bright_green_bg)               => { $crate::BRIGHT_GREEN_BG };
+    (bright_yellow_bg)              => { $crate::BRIGHT_YELLOW_BG };
+    (bright_blue_bg)                => { $crate::BRIGHT_BLUE_BG };
+    (bright_magenta_bg)             => { $crate::BRIGHT_MAGENTA_BG };
+    (bright_cyan_bg)                => { $crate::BRIGHT_CYAN_BG };
+    (bright_white_bg)               => { $crate::BRIGHT_WHITE_BG };
+    (fg(256: $n:expr))                          => { $crate::color_256_code($n) };
+    (bg(256: $n:expr))                          => { $crate::color_256_bg_code($n) };
+    (fg(rgb: $r:expr, $g:expr, $b:expr))        => { $crate::truecolor_code($r, $g, $b) };
+    (bg(rgb: $r:expr, $g:expr, $b:expr))        => { $crate::truecolor_bg_code($r, $g, $b) };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn style_expansions() {
+        assert_eq!(crate::style!(bold; "x"),                        format!("{BOLD}x{RESET}"));
+        assert_eq!(crate::style!(bold, red; "{}-{}", 1, 2),         format!("{BOLD}{RED}1-2{RESET}"));
+        assert_eq!(crate::style!(bright_white_bg, not_bold; "x"),   format!("{BRIGHT_WHITE_BG}{NOT_BOLD}x{RESET}"));
+        assert_eq!(crate::style!(fg(256: 214); "x"),                format!("{}x{RESET}", color_256(214)));
+        assert_eq!(crate::style!(fg(rgb: 127, 45, 68); "x"),        format!("{}x{RESET}", truecolor(127, 45, 68)));
+        assert_eq!(crate::style!(italic, bg(256: 1 + 1); "x"),      format!("{ITALIC}{}x{RESET}", color_256_bg(2)));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn println_styled_forwards_arguments() {
+        let msg = "printed";
+        crate::println_styled!(green; "{msg} by {}", "println_styled");
+        crate::eprintln_styled!(yellow, underline; "{msg} by {}", "eprintln_styled");
+    }
+
+    #[test]
+    fn hex_literals() {
+        const RED_ISH: Color = crate::hex!("#ff2d44");
+        assert_eq!(RED_ISH,                 Color::Rgb(0xff, 0x2d, 0x44));
+        assert_eq!(crate::hex!("000"),      Color::Rgb(0, 0, 0));
+        assert_eq!(crate::hex!("#aBcDeF"),  Color::Rgb(0xab, 0xcd, 0xef));
+
+        for hex in ["#ff2d44", "f2a", "#FFF", "012345"] {
+            assert_eq!(parse_hex(hex), Ok(crate::hex::parse_hex_literal(hex)));
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn style_captures_arguments() {
+        let n = 3;
+        assert_eq!(crate::style!(dim; "{n} items"), format!("{DIM}3 items{RESET}"));
+    }
+}
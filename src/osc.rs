@@ -0,0 +1,72 @@
+// Operating System Command (OSC) sequences.
+
+use alloc::{format, string::String};
+
+/// Make `text` a clickable link to `url`, using the OSC 8 hyperlink sequence. Terminals that don't
+/// support OSC 8 should simply print `text`. If `text` is empty, the URL itself is used as the
+/// visible text so that the link isn't invisible.
+///
+/// The URL is neither escaped nor validated. Don't pass a URL (or text) containing control
+/// characters such as `\x1b` or `\x07`, because they can end the sequence early and cause the
+/// rest of the string to be interpreted as control codes.
+///
+/// ```
+/// use flower_pot::*;
+///
+/// println!("see {}", hyperlink("https://example.com", "the example site"));
+/// ```
+pub fn hyperlink(url: &str, text: &str) -> String {
+    let text = if text.is_empty() { url } else { text };
+    format!("\x1b]8;;{url}\x1b\\{text}\x1b]8;;\x1b\\")
+}
+
+/// Set the title of the terminal window (and of its icon, or its tab in some terminals) to
+/// `title`, using the OSC 0 sequence `\x1b]0;TITLE\x07`. Requires the `window-title` feature.
+///
+/// Not all terminals support this, and some let the user turn it off; those should ignore the
+/// sequence. The title is neither escaped nor validated, so don't pass one containing control
+/// characters such as `\x1b` or `\x07`, because they can end the sequence early and cause the rest
+/// of the title to be interpreted as control codes.
+///
+/// ```
+/// use flower_pot::*;
+///
+/// print!("{}", window_title("build: running"));
+/// ```
+#[cfg(feature = "window-title")]
+pub fn window_title(title: &str) -> String {
+    format!("\x1b]0;{title}\x07")
+}
+
+/// Like `window_title`, but uses the OSC 2 sequence `\x1b]2;TITLE\x07`, which sets only the
+/// window title and leaves the icon title alone. Requires the `window-title` feature.
+#[cfg(feature = "window-title")]
+pub fn window_title_only(title: &str) -> String {
+    format!("\x1b]2;{title}\x07")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hyperlink_sequence() {
+        assert_eq!(
+            hyperlink("https://example.com", "example"),
+            "\x1b]8;;https://example.com\x1b\\example\x1b]8;;\x1b\\",
+        );
+    }
+
+    #[test]
+    fn hyperlink_empty_text() {
+        assert_eq!(hyperlink("https://a.b", ""), hyperlink("https://a.b", "https://a.b"));
+    }
+
+    #[test]
+    #[cfg(feature = "window-title")]
+    fn window_title_sequences() {
+        assert_eq!(window_title("build"),       "\x1b]0;build\x07");
+        assert_eq!(window_title_only("build"),  "\x1b]2;build\x07");
+        assert_eq!(crate::strip_ansi(&window_title("x")), "");
+    }
+}
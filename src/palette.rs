@@ -0,0 +1,618 @@
+// The 256-color palette.
+
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+
+use crate::{Color, NamedColor};
+
+#[cfg(feature = "alloc")]
+use crate::{color_256, color_256_bg};
+
+/// The six channel intensities used by the 6×6×6 color cube (palette indices 16–231) in xterm and
+/// most terminals that copy it.
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// The RGB values of the 16 named colors in xterm's default palette.
+const NAMED_RGB: [(u8, u8, u8); 16] = [
+    (0, 0, 0),          (205, 0, 0),        (0, 205, 0),        (205, 205, 0),
+    (0, 0, 238),        (205, 0, 205),      (0, 205, 205),      (229, 229, 229),
+    (127, 127, 127),    (255, 0, 0),        (0, 255, 0),        (255, 255, 0),
+    (92, 92, 255),      (255, 0, 255),      (0, 255, 255),      (255, 255, 255),
+];
+
+/// Expand to an array of 256 string literals, one for each palette index, by calling
+/// `concat!(prefix, n, "m")` for each `n` in 0..=255.
+macro_rules! palette_table {
+    ($prefix:literal) => {
+        palette_table!(@expand $prefix;
+            0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15
+            16 17 18 19 20 21 22 23 24 25 26 27 28 29 30 31
+            32 33 34 35 36 37 38 39 40 41 42 43 44 45 46 47
+            48 49 50 51 52 53 54 55 56 57 58 59 60 61 62 63
+            64 65 66 67 68 69 70 71 72 73 74 75 76 77 78 79
+            80 81 82 83 84 85 86 87 88 89 90 91 92 93 94 95
+            96 97 98 99 100 101 102 103 104 105 106 107 108 109 110 111
+            112 113 114 115 116 117 118 119 120 121 122 123 124 125 126 127
+            128 129 130 131 132 133 134 135 136 137 138 139 140 141 142 143
+            144 145 146 147 148 149 150 151 152 153 154 155 156 157 158 159
+            160 161 162 163 164 165 166 167 168 169 170 171 172 173 174 175
+            176 177 178 179 180 181 182 183 184 185 186 187 188 189 190 191
+            192 193 194 195 196 197 198 199 200 201 202 203 204 205 206 207
+            208 209 210 211 212 213 214 215 216 217 218 219 220 221 222 223
+            224 225 226 227 228 229 230 231 232 233 234 235 236 237 238 239
+            240 241 242 243 244 245 246 247 248 249 250 251 252 253 254 255
+        )
+    };
+    (@expand $prefix:literal; $($n:literal)*) => {
+        [$(concat!($prefix, $n, "m")),*]
+    };
+}
+
+const COLOR_256_TABLE:      [&str; 256] = palette_table!("\x1b[38;5;");
+const COLOR_256_BG_TABLE:   [&str; 256] = palette_table!("\x1b[48;5;");
+
+/// Same as `color_256`, but returns a `&'static str` from a precomputed table instead of
+/// allocating a new String on every call. Because it's a `const fn`, it can also be used to define
+/// constants:
+///
+/// ```
+/// use flower_pot::*;
+/// # #[cfg(feature = "alloc")] {
+///
+/// const ORANGE: &str = color_256_str(214);
+/// assert_eq!(ORANGE, color_256(214));
+/// # }
+/// ```
+pub const fn color_256_str(n: u8) -> &'static str {
+    COLOR_256_TABLE[n as usize]
+}
+
+/// Same as `color_256_bg`, but returns a `&'static str` from a precomputed table instead of
+/// allocating a new String on every call. Like `color_256_str`, it can be used to define constants.
+pub const fn color_256_bg_str(n: u8) -> &'static str {
+    COLOR_256_BG_TABLE[n as usize]
+}
+
+/// The four ranges of the 256-color palette, as returned by `palette_kind`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PaletteKind {
+    /// Indices 0–7: the 8 standard named colors, from black to white.
+    Standard,
+    /// Indices 8–15: the bright versions of the standard colors.
+    Bright,
+    /// Indices 16–231: the 6×6×6 color cube (see `cube`).
+    Cube,
+    /// Indices 232–255: the 24-step grayscale ramp (see `gray`).
+    Grayscale,
+}
+
+/// Which range of the 256-color palette index `n` belongs to.
+///
+/// ```
+/// use flower_pot::*;
+///
+/// assert_eq!(palette_kind(9), PaletteKind::Bright);
+/// assert_eq!(palette_kind(208), PaletteKind::Cube);
+/// ```
+pub const fn palette_kind(n: u8) -> PaletteKind {
+    match n {
+        0..8    => PaletteKind::Standard,
+        8..16   => PaletteKind::Bright,
+        16..232 => PaletteKind::Cube,
+        _       => PaletteKind::Grayscale,
+    }
+}
+
+/// Whether palette index `n` is one of the 8 standard named colors (0–7).
+pub const fn is_standard(n: u8) -> bool {
+    matches!(palette_kind(n), PaletteKind::Standard)
+}
+
+/// Whether palette index `n` is one of the 8 bright named colors (8–15).
+pub const fn is_bright(n: u8) -> bool {
+    matches!(palette_kind(n), PaletteKind::Bright)
+}
+
+/// Whether palette index `n` is in the 6×6×6 color cube (16–231).
+pub const fn is_cube(n: u8) -> bool {
+    matches!(palette_kind(n), PaletteKind::Cube)
+}
+
+/// Whether palette index `n` is in the grayscale ramp (232–255).
+pub const fn is_grayscale(n: u8) -> bool {
+    matches!(palette_kind(n), PaletteKind::Grayscale)
+}
+
+/// The approximate RGB value of the *n*th color in the 256-color palette. Terminals are free to
+/// choose their own colors, especially for the first 16 entries (the named colors), so this uses
+/// the defaults of xterm, which many other terminals copy:
+///
+/// - Indices 0–15 are the named colors, with the values given by `NamedColor::to_rgb`.
+/// - Indices 16–231 are the 6×6×6 color cube. Index `16 + 36 * r + 6 * g + b` has the channel
+///   intensities `[0, 95, 135, 175, 215, 255][r]`, `[...][g]`, and `[...][b]`.
+/// - Indices 232–255 are the grayscale ramp. Index `232 + i` has all three channels equal to
+///   `8 + 10 * i`.
+pub const fn palette_to_rgb(n: u8) -> (u8, u8, u8) {
+    match n {
+        0..16 => NAMED_RGB[n as usize],
+        16..232 => {
+            let i = n - 16;
+            (CUBE_LEVELS[(i / 36) as usize], CUBE_LEVELS[(i / 6 % 6) as usize], CUBE_LEVELS[(i % 6) as usize])
+        }
+        _ => {
+            let v = 8 + 10 * (n - 232);
+            (v, v, v)
+        }
+    }
+}
+
+/// The RGB values of all 256 palette colors, indexed by palette index, such as for drawing a grid
+/// of swatches. Entry `n` is `palette_to_rgb(n)`, so this uses the same xterm defaults:
+///
+/// - Entries 0–15 are the named colors (see `NamedColor::to_rgb`).
+/// - Entries 16–231 are the 6×6×6 color cube. Entry `16 + 36 * r + 6 * g + b` (where `r`, `g`, and
+///   `b` are between 0 and 5) has the channel values `LEVELS[r]`, `LEVELS[g]`, and `LEVELS[b]`,
+///   where `LEVELS` is `[0, 95, 135, 175, 215, 255]`: 0 for step 0, and `55 + 40 * step` for the
+///   other steps.
+/// - Entries 232–255 are the grayscale ramp. Entry `232 + i` has all three channels equal to
+///   `8 + 10 * i`, from 8 to 238.
+///
+/// ```
+/// use flower_pot::*;
+///
+/// let table = palette_rgb_table();
+/// assert_eq!(table[208], (255, 135, 0));
+/// assert_eq!(table[244], (128, 128, 128));
+/// ```
+pub const fn palette_rgb_table() -> [(u8, u8, u8); 256] {
+    let mut table = [(0, 0, 0); 256];
+    let mut n = 0;
+    while n < 256 {
+        table[n] = palette_to_rgb(n as u8);
+        n += 1;
+    }
+    table
+}
+
+const fn min_u8(a: u8, b: u8) -> u8 {
+    if a < b { a } else { b }
+}
+
+fn distance_squared(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+    let dr = a.0 as i32 - b.0 as i32;
+    let dg = a.1 as i32 - b.1 as i32;
+    let db = a.2 as i32 - b.2 as i32;
+    (dr * dr + dg * dg + db * db) as u32
+}
+
+fn nearest_cube_level(value: u8) -> usize {
+    (0..6).min_by_key(|&i| (CUBE_LEVELS[i] as i32 - value as i32).abs()).unwrap()
+}
+
+/// Find the entry in the 256-color palette nearest to the RGB value (r, g, b), for use with
+/// `color_256` on terminals that don't support truecolor. Only the 6×6×6 color cube (indices
+/// 16–231) and the grayscale ramp (indices 232–255) are considered, because the first 16 colors
+/// vary too much between terminals to be matched against. The cube is assumed to use the channel
+/// intensities 0, 95, 135, 175, 215, and 255, and the ramp the grays 8, 18, 28, ..., 238. "Nearest"
+/// means smallest squared Euclidean distance in RGB space; if the nearest cube entry and the
+/// nearest gray are equally close, the cube entry wins.
+pub fn rgb_to_256(r: u8, g: u8, b: u8) -> u8 {
+    let (ri, gi, bi) = (nearest_cube_level(r), nearest_cube_level(g), nearest_cube_level(b));
+    let cube_index = 16 + 36 * ri + 6 * gi + bi;
+    let cube_rgb = (CUBE_LEVELS[ri], CUBE_LEVELS[gi], CUBE_LEVELS[bi]);
+
+    let gray_level = (0..24u8)
+        .min_by_key(|&i| {
+            let v = 8 + 10 * i;
+            distance_squared((r, g, b), (v, v, v))
+        })
+        .unwrap();
+    let gray_value = 8 + 10 * gray_level;
+
+    if distance_squared((r, g, b), (gray_value, gray_value, gray_value)) < distance_squared((r, g, b), cube_rgb) {
+        232 + gray_level
+    } else {
+        cube_index as u8
+    }
+}
+
+fn nearest_named(rgb: (u8, u8, u8), count: u8) -> NamedColor {
+    let index = (0..count).min_by_key(|&i| distance_squared(rgb, NAMED_RGB[i as usize])).unwrap();
+    NamedColor::from_index(index).unwrap()
+}
+
+/// Find the one of the 8 standard named colors (black through white, without the bright versions)
+/// nearest to the RGB value (r, g, b), for terminals that support nothing else. The named colors
+/// are assumed to have xterm's default values (see `NamedColor::to_rgb`). "Nearest" means smallest
+/// squared Euclidean distance in RGB space; if several colors are equally close, the one that
+/// comes first in `NamedColor::all()` wins. Use `rgb_to_named_bright` to include the bright
+/// versions.
+///
+/// ```
+/// use flower_pot::*;
+///
+/// assert_eq!(rgb_to_named(255, 60, 0), NamedColor::Red); // a red-orange
+/// ```
+pub fn rgb_to_named(r: u8, g: u8, b: u8) -> NamedColor {
+    nearest_named((r, g, b), 8)
+}
+
+/// Like `rgb_to_named`, but chooses from all 16 named colors, including the bright versions.
+pub fn rgb_to_named_bright(r: u8, g: u8, b: u8) -> NamedColor {
+    nearest_named((r, g, b), 16)
+}
+
+/// The index in the 256-color palette of the color at position (r, g, b) in the 6×6×6 color cube,
+/// where each component is between 0 and 5. Components above 5 are treated as 5 (use
+/// `cube_checked` to reject them instead). See `palette_to_rgb` for the RGB values of the cube.
+///
+/// ```
+/// use flower_pot::*;
+///
+/// assert_eq!(cube(5, 2, 0), 208); // orange
+/// ```
+pub const fn cube(r: u8, g: u8, b: u8) -> u8 {
+    16 + 36 * min_u8(r, 5) + 6 * min_u8(g, 5) + min_u8(b, 5)
+}
+
+/// Like `cube`, but returns `None` instead of saturating if any component is above 5.
+///
+/// ```
+/// use flower_pot::*;
+///
+/// assert_eq!(cube_checked(5, 2, 0), Some(208));
+/// assert_eq!(cube_checked(6, 2, 0), None);
+/// ```
+pub const fn cube_checked(r: u8, g: u8, b: u8) -> Option<u8> {
+    if r > 5 || g > 5 || b > 5 { None } else { Some(cube(r, g, b)) }
+}
+
+/// The index in the 256-color palette of the shade of gray at `level` in the 24-step grayscale
+/// ramp (see `gray`), or `None` if `level` is above 23.
+///
+/// ```
+/// use flower_pot::*;
+///
+/// assert_eq!(gray_checked(0), Some(232));
+/// assert_eq!(gray_checked(24), None);
+/// ```
+pub const fn gray_checked(level: u8) -> Option<u8> {
+    if level > 23 { None } else { Some(232 + level) }
+}
+
+/// An index into the 256-color palette, built with a constructor for the range it belongs to (see
+/// `PaletteKind`), each of which checks its arguments and returns `None` if they are out of range.
+///
+/// ```
+/// use flower_pot::*;
+///
+/// let orange = Ansi256::cube(5, 2, 0).unwrap();
+/// assert_eq!(orange.index(), 208);
+/// assert_eq!(orange.kind(), PaletteKind::Cube);
+/// assert_eq!(orange.fg(), color_256_str(208));
+///
+/// assert_eq!(Ansi256::bright(8), None);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Ansi256(pub u8);
+
+impl Ansi256 {
+    /// The *n*th of the 8 standard named colors (indices 0–7), or `None` if `n` is above 7.
+    pub const fn standard(n: u8) -> Option<Ansi256> {
+        if n > 7 { None } else { Some(Ansi256(n)) }
+    }
+
+    /// The *n*th of the 8 bright named colors (indices 8–15), or `None` if `n` is above 7.
+    pub const fn bright(n: u8) -> Option<Ansi256> {
+        if n > 7 { None } else { Some(Ansi256(8 + n)) }
+    }
+
+    /// The color at position (r, g, b) in the 6×6×6 color cube (indices 16–231), or `None` if any
+    /// component is above 5 (see `cube_checked`).
+    pub const fn cube(r: u8, g: u8, b: u8) -> Option<Ansi256> {
+        match cube_checked(r, g, b) {
+            Some(n) => Some(Ansi256(n)),
+            None    => None,
+        }
+    }
+
+    /// The shade of gray at `level` in the 24-step grayscale ramp (indices 232–255), or `None` if
+    /// `level` is above 23 (see `gray_checked`).
+    pub const fn gray(level: u8) -> Option<Ansi256> {
+        match gray_checked(level) {
+            Some(n) => Some(Ansi256(n)),
+            None    => None,
+        }
+    }
+
+    /// The palette index.
+    pub const fn index(self) -> u8 {
+        self.0
+    }
+
+    /// Which range of the palette the color belongs to (see `palette_kind`).
+    pub const fn kind(self) -> PaletteKind {
+        palette_kind(self.0)
+    }
+
+    /// The control code that sets the foreground color to this color (see `color_256_str`).
+    pub const fn fg(self) -> &'static str {
+        color_256_str(self.0)
+    }
+
+    /// The control code that sets the background color to this color (see `color_256_bg_str`).
+    pub const fn bg(self) -> &'static str {
+        color_256_bg_str(self.0)
+    }
+}
+
+impl From<Ansi256> for Color {
+    fn from(color: Ansi256) -> Color {
+        Color::Palette(color.0)
+    }
+}
+
+/// Set the foreground color for the following text to the color at position (r, g, b) in the
+/// 6×6×6 color cube of the 256-color palette. See `cube` for how the position is interpreted.
+pub const fn cube_fg(r: u8, g: u8, b: u8) -> &'static str {
+    color_256_str(cube(r, g, b))
+}
+
+/// Set the background color for the following text to the color at position (r, g, b) in the
+/// 6×6×6 color cube of the 256-color palette. See `cube` for how the position is interpreted.
+pub const fn cube_bg(r: u8, g: u8, b: u8) -> &'static str {
+    color_256_bg_str(cube(r, g, b))
+}
+
+/// Set the foreground color for the following text to a shade of gray from the 24-step grayscale
+/// ramp of the 256-color palette (indices 232–255). `level` 0 is the darkest gray and 23 is the
+/// lightest; levels above 23 are treated as 23 (use `gray_checked` to reject them instead).
+#[cfg(feature = "alloc")]
+pub fn gray(level: u8) -> String {
+    color_256(232 + level.min(23))
+}
+
+/// Set the background color for the following text to a shade of gray from the 24-step grayscale
+/// ramp of the 256-color palette (indices 232–255). `level` 0 is the darkest gray and 23 is the
+/// lightest; levels above 23 are treated as 23 (use `gray_checked` to reject them instead).
+#[cfg(feature = "alloc")]
+pub fn gray_bg(level: u8) -> String {
+    color_256_bg(232 + level.min(23))
+}
+
+#[cfg(feature = "alloc")]
+fn fraction_to_gray_level(f: f32) -> u8 {
+    if f.is_nan() {
+        0
+    } else {
+        (f.clamp(0.0, 1.0) * 23.0 + 0.5) as u8
+    }
+}
+
+/// Like `gray`, but takes the shade as a fraction between 0.0 (darkest) and 1.0 (lightest), which
+/// is rounded to the nearest of the 24 levels. Values outside that range are clamped, and NaN is
+/// treated as 0.0.
+#[cfg(feature = "alloc")]
+pub fn gray_fraction(f: f32) -> String {
+    gray(fraction_to_gray_level(f))
+}
+
+/// Like `gray_bg`, but takes the shade as a fraction between 0.0 (darkest) and 1.0 (lightest),
+/// which is rounded to the nearest of the 24 levels. Values outside that range are clamped, and NaN
+/// is treated as 0.0.
+#[cfg(feature = "alloc")]
+pub fn gray_fraction_bg(f: f32) -> String {
+    gray_bg(fraction_to_gray_level(f))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn static_table_matches() {
+        for n in 0..=255 {
+            assert_eq!(color_256_str(n),    color_256(n));
+            assert_eq!(color_256_bg_str(n), color_256_bg(n));
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn const_contexts() {
+        const FG:   &str = color_256_str(0);
+        const BG:   &str = color_256_bg_str(255);
+        const CUBE: &str = cube_fg(5, 2, 0);
+
+        assert_eq!(FG,      "\x1b[38;5;0m");
+        assert_eq!(BG,      "\x1b[48;5;255m");
+        assert_eq!(CUBE,    color_256(208));
+    }
+
+    #[test]
+    fn palette_rgb_values() {
+        assert_eq!(palette_to_rgb(0),   (0, 0, 0));
+        assert_eq!(palette_to_rgb(9),   (255, 0, 0));
+        assert_eq!(palette_to_rgb(16),  (0, 0, 0));
+        assert_eq!(palette_to_rgb(21),  (0, 0, 255));
+        assert_eq!(palette_to_rgb(208), (255, 135, 0));
+        assert_eq!(palette_to_rgb(231), (255, 255, 255));
+        assert_eq!(palette_to_rgb(232), (8, 8, 8));
+        assert_eq!(palette_to_rgb(255), (238, 238, 238));
+
+        for &color in NamedColor::all() {
+            assert_eq!(palette_to_rgb(color as u8), color.to_rgb());
+        }
+    }
+
+    #[test]
+    fn palette_kinds() {
+        let boundaries = [
+            (0,     PaletteKind::Standard),
+            (7,     PaletteKind::Standard),
+            (8,     PaletteKind::Bright),
+            (15,    PaletteKind::Bright),
+            (16,    PaletteKind::Cube),
+            (231,   PaletteKind::Cube),
+            (232,   PaletteKind::Grayscale),
+            (255,   PaletteKind::Grayscale),
+        ];
+
+        for (n, kind) in boundaries {
+            assert_eq!(palette_kind(n), kind);
+            assert_eq!(is_standard(n),  kind == PaletteKind::Standard);
+            assert_eq!(is_bright(n),    kind == PaletteKind::Bright);
+            assert_eq!(is_cube(n),      kind == PaletteKind::Cube);
+            assert_eq!(is_grayscale(n), kind == PaletteKind::Grayscale);
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn ansi256_ranges() {
+        for n in 0..8 {
+            assert_eq!(Ansi256::standard(n).unwrap().kind(),    PaletteKind::Standard);
+            assert_eq!(Ansi256::bright(n).unwrap().kind(),      PaletteKind::Bright);
+        }
+        for level in 0..24 {
+            assert_eq!(Ansi256::gray(level).unwrap().kind(),    PaletteKind::Grayscale);
+        }
+        for r in 0..6 {
+            for g in 0..6 {
+                for b in 0..6 {
+                    assert_eq!(Ansi256::cube(r, g, b).unwrap().index(), cube(r, g, b));
+                    assert_eq!(Ansi256::cube(r, g, b).unwrap().kind(),  PaletteKind::Cube);
+                }
+            }
+        }
+
+        assert_eq!(Ansi256::standard(0),    Some(Ansi256(0)));
+        assert_eq!(Ansi256::bright(7),      Some(Ansi256(15)));
+        assert_eq!(Ansi256::gray(23),       Some(Ansi256(255)));
+        assert_eq!(Ansi256::standard(8),    None);
+        assert_eq!(Ansi256::cube(0, 6, 0),  None);
+        assert_eq!(Ansi256::gray(24),       None);
+
+        assert_eq!(Ansi256(9).bg(),         color_256_bg(9));
+        assert_eq!(Color::from(Ansi256(9)), Color::Palette(9));
+    }
+
+    #[test]
+    fn rgb_table() {
+        let table = palette_rgb_table();
+        for n in 0..=255 {
+            assert_eq!(table[n as usize], palette_to_rgb(n));
+        }
+
+        for (step, level) in CUBE_LEVELS.into_iter().enumerate().skip(1) {
+            assert_eq!(level, 55 + 40 * step as u8);
+        }
+    }
+
+    #[test]
+    fn rgb_to_256_round_trips() {
+        for n in 16..=255 {
+            let (r, g, b) = palette_to_rgb(n);
+            assert_eq!(rgb_to_256(r, g, b), n);
+        }
+    }
+
+    #[test]
+    fn rgb_to_256_extremes() {
+        assert_eq!(rgb_to_256(0, 0, 0),         16);
+        assert_eq!(rgb_to_256(255, 255, 255),   231);
+        assert_eq!(rgb_to_256(255, 0, 0),       196);
+        assert_eq!(rgb_to_256(0, 255, 0),       46);
+        assert_eq!(rgb_to_256(0, 0, 255),       21);
+    }
+
+    #[test]
+    fn rgb_to_256_grays() {
+        assert_eq!(rgb_to_256(128, 128, 128),   244);
+        assert_eq!(rgb_to_256(8, 8, 8),         232);
+        assert_eq!(rgb_to_256(238, 238, 238),   255);
+        assert_eq!(rgb_to_256(135, 135, 135),   102);
+    }
+
+    #[test]
+    fn rgb_to_256_colors() {
+        assert_eq!(rgb_to_256(0xff, 0x87, 0x00), 208);
+        assert_eq!(rgb_to_256(0xfd, 0x88, 0x02), 208);
+    }
+
+    #[test]
+    fn rgb_to_named_primaries() {
+        use NamedColor::*;
+
+        assert_eq!(rgb_to_named(0, 0, 0),           Black);
+        assert_eq!(rgb_to_named(255, 0, 0),         Red);
+        assert_eq!(rgb_to_named(0, 255, 0),         Green);
+        assert_eq!(rgb_to_named(255, 255, 0),       Yellow);
+        assert_eq!(rgb_to_named(0, 0, 255),         Blue);
+        assert_eq!(rgb_to_named(255, 0, 255),       Magenta);
+        assert_eq!(rgb_to_named(0, 255, 255),       Cyan);
+        assert_eq!(rgb_to_named(255, 255, 255),     White);
+
+        assert_eq!(rgb_to_named_bright(255, 0, 0),      BrightRed);
+        assert_eq!(rgb_to_named_bright(0, 0, 255),      Blue);
+        assert_eq!(rgb_to_named_bright(255, 255, 255),  BrightWhite);
+        assert_eq!(rgb_to_named_bright(120, 130, 125),  BrightBlack);
+
+        for &color in NamedColor::all() {
+            let (r, g, b) = color.to_rgb();
+            assert_eq!(rgb_to_named_bright(r, g, b), color);
+            if (color as u8) < 8 {
+                assert_eq!(rgb_to_named(r, g, b), color);
+            }
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn cube_indices() {
+        assert_eq!(cube(0, 0, 0),       16);
+        assert_eq!(cube(5, 5, 5),       231);
+        assert_eq!(cube(1, 2, 3),       16 + 36 + 12 + 3);
+        assert_eq!(cube(9, 0, 200),     cube(5, 0, 5));
+        assert_eq!(cube_fg(5, 0, 0),    color_256(196));
+        assert_eq!(cube_bg(0, 0, 5),    color_256_bg(21));
+    }
+
+    #[test]
+    fn checked_bounds() {
+        assert_eq!(cube_checked(0, 0, 0), Some(16));
+        assert_eq!(cube_checked(5, 5, 5), Some(231));
+        assert_eq!(cube_checked(6, 0, 0), None);
+        assert_eq!(cube_checked(0, 6, 0), None);
+        assert_eq!(cube_checked(0, 0, 6), None);
+        assert_eq!(cube_checked(255, 0, 0), None);
+
+        assert_eq!(gray_checked(0), Some(232));
+        assert_eq!(gray_checked(23), Some(255));
+        assert_eq!(gray_checked(24), None);
+        assert_eq!(gray_checked(255), None);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn gray_levels() {
+        assert_eq!(gray(0),         color_256(232));
+        assert_eq!(gray(23),        color_256(255));
+        assert_eq!(gray(24),        color_256(255));
+        assert_eq!(gray(255),       color_256(255));
+        assert_eq!(gray_bg(10),     color_256_bg(242));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn gray_fractions() {
+        assert_eq!(gray_fraction(0.0),      gray(0));
+        assert_eq!(gray_fraction(1.0),      gray(23));
+        assert_eq!(gray_fraction(0.4),      gray(9));
+        assert_eq!(gray_fraction(-3.0),     gray(0));
+        assert_eq!(gray_fraction(7.5),      gray(23));
+        assert_eq!(gray_fraction(f32::NAN), gray(0));
+        assert_eq!(gray_fraction_bg(0.5),   gray_bg(12));
+    }
+}
@@ -0,0 +1,384 @@
+// Color math on RGB triples.
+
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+
+#[cfg(feature = "alloc")]
+use crate::{truecolor, truecolor_bg};
+
+/// Convert a float in the range 0.0–1.0 to a channel value in the range 0–255, rounding to the
+/// nearest value. Out-of-range values saturate and NaN becomes 0.
+pub(crate) fn unit_to_channel(v: f32) -> u8 {
+    if v.is_nan() {
+        0
+    } else {
+        (v.clamp(0.0, 1.0) * 255.0 + 0.5) as u8
+    }
+}
+
+/// The linear-light value (0.0–1.0) of each sRGB channel value, precomputed with the standard
+/// sRGB transfer function, because `f32::powf` isn't available without `std`.
+const SRGB_TO_LINEAR: [f32; 256] = [
+    0.0, 0.000303527, 0.000607054, 0.000910581, 0.001214108, 0.001517635, 0.001821162, 0.0021246888,
+    0.002428216, 0.0027317428, 0.00303527, 0.0033465358, 0.0036765074, 0.004024717, 0.004391442, 0.0047769533,
+    0.0051815165, 0.0056053917, 0.006048833, 0.0065120906, 0.00699541, 0.007499032, 0.008023193, 0.008568126,
+    0.009134059, 0.009721218, 0.010329823, 0.010960094, 0.011612245, 0.012286488, 0.0129830325, 0.013702083,
+    0.014443844, 0.015208514, 0.015996294, 0.016807375, 0.017641954, 0.01850022, 0.019382361, 0.020288562,
+    0.02121901, 0.022173885, 0.023153367, 0.024157632, 0.02518686, 0.026241222, 0.027320892, 0.02842604,
+    0.029556835, 0.030713445, 0.031896032, 0.033104766, 0.034339808, 0.035601314, 0.03688945, 0.038204372,
+    0.039546236, 0.0409152, 0.04231141, 0.04373503, 0.045186203, 0.046665087, 0.048171826, 0.049706567,
+    0.051269457, 0.052860647, 0.054480277, 0.05612849, 0.05780543, 0.059511237, 0.061246052, 0.063010015,
+    0.064803265, 0.06662594, 0.06847817, 0.070360094, 0.07227185, 0.07421357, 0.07618538, 0.07818742,
+    0.08021982, 0.08228271, 0.08437621, 0.08650046, 0.08865558, 0.09084171, 0.093058966, 0.09530747,
+    0.09758735, 0.099898726, 0.10224173, 0.104616486, 0.107023105, 0.10946171, 0.11193243, 0.114435375,
+    0.116970666, 0.11953843, 0.122138776, 0.12477182, 0.12743768, 0.13013647, 0.13286832, 0.13563333,
+    0.13843161, 0.14126329, 0.14412847, 0.14702727, 0.14995979, 0.15292615, 0.15592647, 0.15896083,
+    0.16202937, 0.1651322, 0.1682694, 0.17144111, 0.1746474, 0.17788842, 0.18116425, 0.18447499,
+    0.18782078, 0.19120169, 0.19461784, 0.19806932, 0.20155625, 0.20507874, 0.20863687, 0.21223076,
+    0.2158605, 0.2195262, 0.22322796, 0.22696587, 0.23074006, 0.23455058, 0.23839757, 0.24228112,
+    0.24620132, 0.25015828, 0.2541521, 0.25818285, 0.26225066, 0.2663556, 0.2704978, 0.2746773,
+    0.27889428, 0.28314874, 0.28744084, 0.29177064, 0.29613826, 0.30054379, 0.3049873, 0.30946892,
+    0.31398872, 0.31854677, 0.3231432, 0.3277781, 0.33245152, 0.33716363, 0.34191442, 0.34670407,
+    0.3515326, 0.35640013, 0.3613068, 0.3662526, 0.3712377, 0.37626213, 0.38132602, 0.38642943,
+    0.39157248, 0.39675522, 0.40197778, 0.4072402, 0.4125426, 0.41788507, 0.42326766, 0.4286905,
+    0.43415365, 0.43965718, 0.4452012, 0.4507858, 0.45641103, 0.462077, 0.4677838, 0.47353148,
+    0.47932017, 0.48514995, 0.49102086, 0.49693298, 0.5028865, 0.50888133, 0.5149177, 0.52099556,
+    0.5271151, 0.5332764, 0.5394795, 0.54572445, 0.55201143, 0.5583404, 0.5647115, 0.57112485,
+    0.57758045, 0.58407843, 0.59061885, 0.59720176, 0.60382736, 0.61049557, 0.6172066, 0.6239604,
+    0.63075715, 0.63759685, 0.6444797, 0.65140563, 0.65837485, 0.6653873, 0.67244315, 0.6795425,
+    0.6866853, 0.69387174, 0.7011019, 0.70837575, 0.7156935, 0.7230551, 0.73046076, 0.7379104,
+    0.7454042, 0.7529422, 0.7605245, 0.76815116, 0.7758222, 0.7835378, 0.7912979, 0.7991027,
+    0.80695224, 0.8148466, 0.82278574, 0.8307699, 0.838799, 0.8468732, 0.8549926, 0.8631572,
+    0.8713671, 0.8796224, 0.8879231, 0.8962694, 0.9046612, 0.91309863, 0.92158186, 0.9301109,
+    0.9386857, 0.9473065, 0.9559733, 0.9646863, 0.9734453, 0.9822506, 0.9911021, 1.0,
+];
+
+/// Convert an sRGB channel value to linear light (0.0–1.0).
+pub(crate) fn srgb_to_linear(c: u8) -> f32 {
+    SRGB_TO_LINEAR[c as usize]
+}
+
+/// The relative luminance of an RGB color as defined by WCAG 2: 0.0 for black and 1.0 for white.
+pub fn relative_luminance(rgb: (u8, u8, u8)) -> f32 {
+    0.2126 * srgb_to_linear(rgb.0) + 0.7152 * srgb_to_linear(rgb.1) + 0.0722 * srgb_to_linear(rgb.2)
+}
+
+/// The WCAG 2 contrast ratio between two RGB colors, from 1.0 (no contrast) to 21.0 (black and
+/// white). The order of the arguments doesn't matter. WCAG's AA level asks for a ratio of at least
+/// 4.5 for normal text and 3.0 for large text, and its AAA level asks for 7.0 and 4.5.
+pub fn contrast_ratio(fg: (u8, u8, u8), bg: (u8, u8, u8)) -> f32 {
+    let (a, b) = (relative_luminance(fg), relative_luminance(bg));
+    let (lighter, darker) = if a > b { (a, b) } else { (b, a) };
+    (lighter + 0.05) / (darker + 0.05)
+}
+
+/// Pick black or white, whichever has the higher contrast ratio (see `contrast_ratio`) against
+/// the background color `bg`. Black wins ties.
+pub fn readable_fg(bg: (u8, u8, u8)) -> (u8, u8, u8) {
+    let (black, white) = ((0, 0, 0), (255, 255, 255));
+    if contrast_ratio(black, bg) >= contrast_ratio(white, bg) { black } else { white }
+}
+
+/// Set the foreground color to black or white, whichever is more readable against the background
+/// color `bg` (see `readable_fg`). Not supported on all terminals (see `truecolor`).
+#[cfg(feature = "alloc")]
+pub fn readable_truecolor(bg: (u8, u8, u8)) -> String {
+    let (r, g, b) = readable_fg(bg);
+    truecolor(r, g, b)
+}
+
+fn clamp_fraction(amount: f32) -> f32 {
+    if amount.is_nan() { 0.0 } else { amount.clamp(0.0, 1.0) }
+}
+
+/// Move each channel of `rgb` a fraction `amount` of the way towards white, so 0.0 leaves the
+/// color unchanged and 1.0 gives white. The blending is done directly on the sRGB channel values.
+/// `amount` is clamped to the range 0.0–1.0, and NaN is treated as 0.0.
+pub fn lighten(rgb: (u8, u8, u8), amount: f32) -> (u8, u8, u8) {
+    let amount = clamp_fraction(amount);
+    let channel = |c: u8| (c as f32 + (255.0 - c as f32) * amount + 0.5) as u8;
+    (channel(rgb.0), channel(rgb.1), channel(rgb.2))
+}
+
+/// Move each channel of `rgb` a fraction `amount` of the way towards black, so 0.0 leaves the
+/// color unchanged and 1.0 gives black. The blending is done directly on the sRGB channel values.
+/// `amount` is clamped to the range 0.0–1.0, and NaN is treated as 0.0.
+pub fn darken(rgb: (u8, u8, u8), amount: f32) -> (u8, u8, u8) {
+    let amount = clamp_fraction(amount);
+    let channel = |c: u8| (c as f32 * (1.0 - amount) + 0.5) as u8;
+    (channel(rgb.0), channel(rgb.1), channel(rgb.2))
+}
+
+/// Convert a linear-light value (0.0–1.0) back to the nearest sRGB channel value.
+pub(crate) fn linear_to_srgb(v: f32) -> u8 {
+    let i = SRGB_TO_LINEAR.partition_point(|&x| x < v);
+    match i {
+        0   => 0,
+        256 => 255,
+        i if v - SRGB_TO_LINEAR[i - 1] < SRGB_TO_LINEAR[i] - v => (i - 1) as u8,
+        i   => i as u8,
+    }
+}
+
+/// Mix the colors `a` and `b`: `t` = 0.0 gives `a`, `t` = 1.0 gives `b`, and values in between
+/// interpolate each channel linearly. The interpolation is done directly on the sRGB channel
+/// values, which is what most tools do; see `blend_linear` for a physically-based alternative.
+/// `t` is clamped to the range 0.0–1.0, and NaN is treated as 0.0.
+pub fn blend(a: (u8, u8, u8), b: (u8, u8, u8), t: f32) -> (u8, u8, u8) {
+    let t = clamp_fraction(t);
+    let channel = |a: u8, b: u8| (a as f32 * (1.0 - t) + b as f32 * t + 0.5) as u8;
+    (channel(a.0, b.0), channel(a.1, b.1), channel(a.2, b.2))
+}
+
+/// Like `blend`, but interpolates in linear light (converting the channels out of sRGB, mixing
+/// them, and converting back), which is how light actually mixes. Compared to `blend`, the
+/// midpoint between two colors comes out brighter; for example, the midpoint between black and
+/// white is (188, 188, 188) rather than (128, 128, 128).
+pub fn blend_linear(a: (u8, u8, u8), b: (u8, u8, u8), t: f32) -> (u8, u8, u8) {
+    let t = clamp_fraction(t);
+    let channel = |a: u8, b: u8| linear_to_srgb(srgb_to_linear(a) * (1.0 - t) + srgb_to_linear(b) * t);
+    (channel(a.0, b.0), channel(a.1, b.1), channel(a.2, b.2))
+}
+
+/// Convert the HSL color (h, s, l) to RGB. The hue `h` is in degrees and wraps around modulo 360,
+/// so -120.0, 240.0, and 600.0 all mean the same hue. The saturation `s` and lightness `l` are
+/// fractions that are clamped to the range 0.0–1.0. NaN is treated as 0.0 for all three.
+pub fn hsl_to_rgb(h: f32, s: f32, l: f32) -> (u8, u8, u8) {
+    let h = if h.is_nan() { 0.0 } else { h % 360.0 };
+    let h = if h < 0.0 { h + 360.0 } else { h };
+    let s = if s.is_nan() { 0.0 } else { s.clamp(0.0, 1.0) };
+    let l = if l.is_nan() { 0.0 } else { l.clamp(0.0, 1.0) };
+
+    let chroma = (1.0 - (2.0 * l - 1.0).abs()) * s;
+    let sector = h / 60.0;
+    let x = chroma * (1.0 - (sector % 2.0 - 1.0).abs());
+    let m = l - chroma / 2.0;
+
+    let (r, g, b) = match sector as u32 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+
+    (unit_to_channel(r + m), unit_to_channel(g + m), unit_to_channel(b + m))
+}
+
+/// Convert an RGB color to HSL (see `hsl_to_rgb`), with the hue in degrees in the range 0.0–360.0.
+/// Grays have a hue and saturation of 0.0.
+fn rgb_to_hsl(rgb: (u8, u8, u8)) -> (f32, f32, f32) {
+    let (r, g, b) = (rgb.0 as f32 / 255.0, rgb.1 as f32 / 255.0, rgb.2 as f32 / 255.0);
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let l = (max + min) / 2.0;
+    let chroma = max - min;
+    if chroma == 0.0 {
+        return (0.0, 0.0, l);
+    }
+
+    let s = chroma / (1.0 - (2.0 * l - 1.0).abs());
+    let h = if max == r {
+        (g - b) / chroma
+    } else if max == g {
+        (b - r) / chroma + 2.0
+    } else {
+        (r - g) / chroma + 4.0
+    };
+    let h = h * 60.0;
+    (if h < 0.0 { h + 360.0 } else { h }, s, l)
+}
+
+/// Rotate the hue of `rgb` by `degrees`, keeping its saturation and lightness. The hue wraps
+/// around, so shifting by 360.0 (or -360.0) gives back the same color, and grays are unchanged.
+/// NaN is treated as 0.0. The conversion goes through HSL (see `hsl_to_rgb`), so the result may
+/// differ from an exact rotation by rounding.
+///
+/// ```
+/// use flower_pot::*;
+///
+/// assert_eq!(shift_hue((255, 0, 0), 120.0), (0, 255, 0));
+/// assert_eq!(shift_hue((255, 0, 0), -120.0), (0, 0, 255));
+/// ```
+pub fn shift_hue(rgb: (u8, u8, u8), degrees: f32) -> (u8, u8, u8) {
+    let (h, s, l) = rgb_to_hsl(rgb);
+    let degrees = if degrees.is_nan() { 0.0 } else { degrees % 360.0 };
+    hsl_to_rgb(h + degrees, s, l)
+}
+
+/// Multiply the saturation of `rgb` by `factor`, keeping its hue and lightness: 0.0 gives the gray
+/// of the same lightness, 1.0 leaves the color unchanged, and values above 1.0 make it more vivid.
+/// The resulting saturation is clamped to the range 0.0–1.0, negative factors are treated as 0.0,
+/// and NaN is treated as 1.0. Combine this with `shift_hue`, `lighten`, and `darken` to derive a
+/// whole palette from one base color.
+pub fn adjust_saturation(rgb: (u8, u8, u8), factor: f32) -> (u8, u8, u8) {
+    let (h, s, l) = rgb_to_hsl(rgb);
+    let factor = if factor.is_nan() { 1.0 } else { factor.max(0.0) };
+    hsl_to_rgb(h, s * factor, l)
+}
+
+/// Set the foreground color to the HSL value (h, s, l), with hue `h` in degrees and saturation
+/// `s` and lightness `l` between 0.0 and 1.0. See `hsl_to_rgb` for how out-of-range values are
+/// handled. Not supported on all terminals (see `truecolor`).
+#[cfg(feature = "alloc")]
+pub fn truecolor_hsl(h: f32, s: f32, l: f32) -> String {
+    let (r, g, b) = hsl_to_rgb(h, s, l);
+    truecolor(r, g, b)
+}
+
+/// Set the background color to the HSL value (h, s, l), with hue `h` in degrees and saturation
+/// `s` and lightness `l` between 0.0 and 1.0. See `hsl_to_rgb` for how out-of-range values are
+/// handled. Not supported on all terminals (see `truecolor_bg`).
+#[cfg(feature = "alloc")]
+pub fn truecolor_hsl_bg(h: f32, s: f32, l: f32) -> String {
+    let (r, g, b) = hsl_to_rgb(h, s, l);
+    truecolor_bg(r, g, b)
+}
+
+/// Set the foreground color to the RGB value (r, g, b), with each channel given as a fraction
+/// between 0.0 and 1.0 (as computed by color math) and scaled to 0–255, rounding to the nearest
+/// value. Out-of-range channels saturate, and NaN becomes 0. Not supported on all terminals (see
+/// `truecolor`).
+///
+/// ```
+/// use flower_pot::*;
+///
+/// assert_eq!(truecolor_f(1.0, 0.5, 0.0), truecolor(255, 128, 0));
+/// assert_eq!(truecolor_f(1.7, -0.2, f32::NAN), truecolor(255, 0, 0));
+/// ```
+#[cfg(feature = "alloc")]
+pub fn truecolor_f(r: f32, g: f32, b: f32) -> String {
+    truecolor(unit_to_channel(r), unit_to_channel(g), unit_to_channel(b))
+}
+
+/// Set the background color to the RGB value (r, g, b), with each channel given as a fraction
+/// between 0.0 and 1.0. See `truecolor_f` for how the channels are converted. Not supported on all
+/// terminals (see `truecolor_bg`).
+#[cfg(feature = "alloc")]
+pub fn truecolor_f_bg(r: f32, g: f32, b: f32) -> String {
+    truecolor_bg(unit_to_channel(r), unit_to_channel(g), unit_to_channel(b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hsl_known_values() {
+        assert_eq!(hsl_to_rgb(0.0, 1.0, 0.5),     (255, 0, 0));
+        assert_eq!(hsl_to_rgb(120.0, 1.0, 0.5),   (0, 255, 0));
+        assert_eq!(hsl_to_rgb(240.0, 1.0, 0.5),   (0, 0, 255));
+        assert_eq!(hsl_to_rgb(60.0, 1.0, 0.5),    (255, 255, 0));
+        assert_eq!(hsl_to_rgb(300.0, 1.0, 0.5),   (255, 0, 255));
+        assert_eq!(hsl_to_rgb(0.0, 0.0, 0.0),     (0, 0, 0));
+        assert_eq!(hsl_to_rgb(0.0, 0.0, 1.0),     (255, 255, 255));
+        assert_eq!(hsl_to_rgb(0.0, 0.0, 0.5),     (128, 128, 128));
+        assert_eq!(hsl_to_rgb(210.0, 0.5, 0.25),  (32, 64, 96));
+    }
+
+    #[test]
+    fn hsl_wraps_and_clamps() {
+        assert_eq!(hsl_to_rgb(360.0, 1.0, 0.5),   (255, 0, 0));
+        assert_eq!(hsl_to_rgb(-120.0, 1.0, 0.5),  (0, 0, 255));
+        assert_eq!(hsl_to_rgb(600.0, 1.0, 0.5),   (0, 0, 255));
+        assert_eq!(hsl_to_rgb(0.0, 2.0, 0.5),     (255, 0, 0));
+        assert_eq!(hsl_to_rgb(0.0, 1.0, 1.5),     (255, 255, 255));
+        assert_eq!(hsl_to_rgb(f32::NAN, 1.0, 0.5),(255, 0, 0));
+    }
+
+    #[test]
+    fn hue_and_saturation() {
+        assert_eq!(shift_hue((255, 0, 0), 0.0),                (255, 0, 0));
+        assert_eq!(shift_hue((255, 0, 0), 120.0),              (0, 255, 0));
+        assert_eq!(shift_hue((255, 0, 0), 60.0),               (255, 255, 0));
+        assert_eq!(shift_hue((255, 0, 0), 480.0),              (0, 255, 0));
+        assert_eq!(shift_hue((255, 0, 0), -60.0),              (255, 0, 255));
+        assert_eq!(shift_hue((32, 64, 96), 360.0),             (32, 64, 96));
+        assert_eq!(shift_hue((32, 64, 96), f32::NAN),          (32, 64, 96));
+        assert_eq!(shift_hue((128, 128, 128), 90.0),           (128, 128, 128));
+        assert_eq!(adjust_saturation((255, 0, 0), 0.0),        (128, 128, 128));
+        assert_eq!(adjust_saturation((255, 0, 0), 0.5),        (191, 64, 64));
+        assert_eq!(adjust_saturation((255, 0, 0), 5.0),        (255, 0, 0));
+        assert_eq!(adjust_saturation((32, 64, 96), 1.0),       (32, 64, 96));
+        assert_eq!(adjust_saturation((32, 64, 96), 2.0),       (0, 64, 128));
+        assert_eq!(adjust_saturation((32, 64, 96), -1.0),      (64, 64, 64));
+        assert_eq!(adjust_saturation((32, 64, 96), f32::NAN),  (32, 64, 96));
+
+        for rgb in [(0, 0, 0), (255, 255, 255), (12, 200, 99), (250, 1, 128), (7, 7, 8)] {
+            assert_eq!(shift_hue(rgb, 0.0),         rgb);
+            assert_eq!(adjust_saturation(rgb, 1.0), rgb);
+        }
+    }
+
+    #[test]
+    fn contrast() {
+        let (black, white) = ((0, 0, 0), (255, 255, 255));
+        assert!((contrast_ratio(white, black) - 21.0).abs() < 0.01);
+        assert_eq!(contrast_ratio(white, black), contrast_ratio(black, white));
+        assert!(contrast_ratio(white, black) >= 4.5);
+        assert!((contrast_ratio((12, 34, 56), (12, 34, 56)) - 1.0).abs() < 0.0001);
+        assert!((contrast_ratio((118, 118, 118), white) - 4.54).abs() < 0.01);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn readable() {
+        assert_eq!(readable_fg((0, 0, 0)),          (255, 255, 255));
+        assert_eq!(readable_fg((255, 255, 255)),    (0, 0, 0));
+        assert_eq!(readable_fg((255, 255, 0)),      (0, 0, 0));
+        assert_eq!(readable_fg((0, 0, 170)),        (255, 255, 255));
+        assert_eq!(readable_truecolor((0, 0, 0)),   truecolor(255, 255, 255));
+    }
+
+    #[test]
+    fn lighten_and_darken() {
+        assert_eq!(lighten((100, 0, 255), 0.0),     (100, 0, 255));
+        assert_eq!(lighten((100, 0, 255), 0.5),     (178, 128, 255));
+        assert_eq!(lighten((100, 0, 255), 1.0),     (255, 255, 255));
+        assert_eq!(lighten((100, 0, 255), 3.0),     (255, 255, 255));
+        assert_eq!(darken((100, 0, 255), 0.5),      (50, 0, 128));
+        assert_eq!(darken((100, 0, 255), 1.0),      (0, 0, 0));
+        assert_eq!(darken((100, 0, 255), -1.0),     (100, 0, 255));
+        assert_eq!(darken((100, 0, 255), f32::NAN), (100, 0, 255));
+    }
+
+    #[test]
+    fn blending() {
+        let (a, b) = ((0, 100, 255), (255, 0, 55));
+        assert_eq!(blend(a, b, 0.0),            a);
+        assert_eq!(blend(a, b, 1.0),            b);
+        assert_eq!(blend(a, b, 0.5),            (128, 50, 155));
+        assert_eq!(blend(a, b, -2.0),           a);
+        assert_eq!(blend(a, b, f32::NAN),       a);
+        assert_eq!(blend_linear(a, b, 0.0),     a);
+        assert_eq!(blend_linear(a, b, 1.0),     b);
+        assert_eq!(blend_linear((0, 0, 0), (255, 255, 255), 0.5), (188, 188, 188));
+    }
+
+    #[test]
+    fn linear_round_trip() {
+        for c in 0..=255 {
+            assert_eq!(linear_to_srgb(srgb_to_linear(c)), c);
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn hsl_codes() {
+        assert_eq!(truecolor_hsl(0.0, 1.0, 0.5),      truecolor(255, 0, 0));
+        assert_eq!(truecolor_hsl_bg(120.0, 1.0, 0.5), truecolor_bg(0, 255, 0));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn float_codes() {
+        assert_eq!(truecolor_f(0.0, 0.0, 0.0),                           truecolor(0, 0, 0));
+        assert_eq!(truecolor_f(1.0, 1.0, 1.0),                           truecolor(255, 255, 255));
+        assert_eq!(truecolor_f(0.2, 0.4, 0.6),                           truecolor(51, 102, 153));
+        assert_eq!(truecolor_f(0.998, 0.002, 0.5),                       truecolor(254, 1, 128));
+        assert_eq!(truecolor_f(-1.0, 2.0, f32::NAN),                     truecolor(0, 255, 0));
+        assert_eq!(truecolor_f(f32::INFINITY, f32::NEG_INFINITY, 1e-9),  truecolor(255, 0, 0));
+        assert_eq!(truecolor_f_bg(1.0, 0.5, f32::NAN),                   truecolor_bg(255, 128, 0));
+    }
+}
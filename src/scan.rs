@@ -0,0 +1,124 @@
+// Finding control codes in strings.
+
+/// A piece of a string: either plain text or a single complete control code.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Token<'a> {
+    Text(&'a str),
+    Escape(&'a str),
+}
+
+/// Why there is no complete control code at the start of some bytes (see `escape_len_bytes`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum NoEscape {
+    /// The bytes end before the control code does, so more bytes might complete it.
+    Incomplete,
+    /// The bytes can't be the start of a control code, whatever comes after them.
+    Malformed,
+}
+
+/// The length in bytes of the control code at the start of `bytes`, which must start with `\x1b`.
+/// Recognizes CSI sequences (`\x1b[`, parameters, intermediate bytes, and a final byte, which
+/// includes all SGR codes), OSC sequences (`\x1b]` up to a BEL or `\x1b\\`, which includes
+/// hyperlinks), and two-byte escapes (`\x1b` followed by one more character, other than another
+/// `\x1b`).
+pub(crate) fn escape_len_bytes(bytes: &[u8]) -> Result<usize, NoEscape> {
+    match *bytes.get(1).ok_or(NoEscape::Incomplete)? {
+        b'[' => {
+            let end = bytes[2..].iter().position(|b| !(0x20..=0x3f).contains(b)).ok_or(NoEscape::Incomplete)? + 2;
+            if (0x40..=0x7e).contains(&bytes[end]) { Ok(end + 1) } else { Err(NoEscape::Malformed) }
+        }
+        b']' => {
+            let mut i = 2;
+            loop {
+                match bytes.get(i).ok_or(NoEscape::Incomplete)? {
+                    0x07                                        => return Ok(i + 1),
+                    0x1b if bytes.get(i + 1) == Some(&b'\\')    => return Ok(i + 2),
+                    _                                           => i += 1,
+                }
+            }
+        }
+        // Another escape character starts a new control code instead.
+        0x1b => Err(NoEscape::Malformed),
+        lead => {
+            let len = match lead {
+                0xc0..0xe0  => 2,
+                0xe0..0xf0  => 3,
+                0xf0..      => 4,
+                _           => 1,
+            };
+            if bytes.len() > len { Ok(1 + len) } else { Err(NoEscape::Incomplete) }
+        }
+    }
+}
+
+/// The length in bytes of the control code at the start of `s`, which must start with `\x1b`, or
+/// `None` if the code is incomplete or malformed (see `escape_len_bytes`).
+fn escape_len(s: &str) -> Option<usize> {
+    escape_len_bytes(s.as_bytes()).ok()
+}
+
+/// An iterator over the tokens of a string.
+pub(crate) struct Tokens<'a> {
+    rest: &'a str,
+}
+
+impl<'a> Iterator for Tokens<'a> {
+    type Item = Token<'a>;
+
+    fn next(&mut self) -> Option<Token<'a>> {
+        if self.rest.is_empty() {
+            return None;
+        }
+
+        if self.rest.starts_with('\x1b') && let Some(len) = escape_len(self.rest) {
+            let (escape, rest) = self.rest.split_at(len);
+            self.rest = rest;
+            return Some(Token::Escape(escape));
+        }
+
+        // Text runs up to the next escape character. A malformed or incomplete control code is
+        // treated as text.
+        let first = self.rest.chars().next().map_or(0, char::len_utf8);
+        let len = self.rest[first..].find('\x1b').map_or(self.rest.len(), |i| i + first);
+        let (text, rest) = self.rest.split_at(len);
+        self.rest = rest;
+        Some(Token::Text(text))
+    }
+}
+
+/// Split a string into plain text and control codes.
+pub(crate) fn tokens(s: &str) -> Tokens<'_> {
+    Tokens { rest: s }
+}
+
+/// The parameters of an SGR control code (the part between `\x1b[` and `m`), or `None` if `escape`
+/// is some other kind of control code.
+pub(crate) fn sgr_params(escape: &str) -> Option<&str> {
+    escape.strip_prefix("\x1b[")?.strip_suffix('m')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Token::*;
+
+    fn collect(s: &str) -> Vec<Token<'_>> {
+        tokens(s).collect()
+    }
+
+    #[test]
+    fn tokenize() {
+        assert_eq!(collect(""),                         []);
+        assert_eq!(collect("plain"),                    [Text("plain")]);
+        assert_eq!(collect("\x1b[1mbold\x1b[0m"),       [Escape("\x1b[1m"), Text("bold"), Escape("\x1b[0m")]);
+        assert_eq!(collect("a\x1b[1m\x1b[31mb"),        [Text("a"), Escape("\x1b[1m"), Escape("\x1b[31m"), Text("b")]);
+        assert_eq!(collect("\x1b]8;;url\x1b\\x"),       [Escape("\x1b]8;;url\x1b\\"), Text("x")]);
+        assert_eq!(collect("\x1b]0;title\x07x"),        [Escape("\x1b]0;title\x07"), Text("x")]);
+        assert_eq!(collect("é\x1b[1m"),                 [Text("é"), Escape("\x1b[1m")]);
+        assert_eq!(collect("\x1b7é"),                   [Escape("\x1b7"), Text("é")]);
+        assert_eq!(collect("a\x1b[2"),                  [Text("a"), Text("\x1b[2")]);
+        assert_eq!(collect("\x1b[2é\x1b[1m"),           [Text("\x1b[2é"), Escape("\x1b[1m")]);
+        assert_eq!(collect("a\x1b"),                    [Text("a"), Text("\x1b")]);
+        assert_eq!(collect("a\x1b\x1b[31mb"),          [Text("a"), Text("\x1b"), Escape("\x1b[31m"), Text("b")]);
+    }
+}
@@ -0,0 +1,160 @@
+// Serde support for colors (requires the `serde` feature).
+
+use core::fmt;
+
+use serde::de::{self, Deserialize, Deserializer, MapAccess, Unexpected, Visitor};
+use serde::ser::{Serialize, SerializeMap, Serializer};
+
+use crate::{parse_hex, Color, NamedColor};
+
+/// Serializes as the color's name, such as `"red"` or `"bright blue"`.
+impl Serialize for NamedColor {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.name())
+    }
+}
+
+/// Deserializes from the color's name, such as `"red"` or `"bright blue"`. The name is
+/// case-insensitive, and the space can also be written as `_` or `-`.
+impl<'de> Deserialize<'de> for NamedColor {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<NamedColor, D::Error> {
+        struct NamedColorVisitor;
+
+        impl Visitor<'_> for NamedColorVisitor {
+            type Value = NamedColor;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("the name of a color, such as \"red\" or \"bright blue\"")
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<NamedColor, E> {
+                NamedColor::from_name(v).ok_or_else(|| E::invalid_value(Unexpected::Str(v), &self))
+            }
+        }
+
+        deserializer.deserialize_str(NamedColorVisitor)
+    }
+}
+
+/// Serializes as one of these forms, which `Deserialize` reads back into the same color:
+///
+/// - `Color::Named` as the color's name, such as `"red"` (see `NamedColor`).
+/// - `Color::Palette` as a map with the single key `"palette"`, such as `{ "palette": 214 }`.
+/// - `Color::Rgb` as a lowercase hex string, such as `"#ff2d44"`.
+impl Serialize for Color {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match *self {
+            Color::Named(color)     => color.serialize(serializer),
+            Color::Palette(n)       => {
+                let mut map = serializer.serialize_map(Some(1))?;
+                map.serialize_entry("palette", &n)?;
+                map.end()
+            }
+            Color::Rgb(r, g, b)     => serializer.collect_str(&format_args!("#{r:02x}{g:02x}{b:02x}")),
+        }
+    }
+}
+
+/// Deserializes from the forms described for `Serialize`. Hex strings must start with `#`, and can
+/// use any of the forms accepted by `parse_hex`, such as `"#f2a"`.
+impl<'de> Deserialize<'de> for Color {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Color, D::Error> {
+        struct PaletteKey;
+
+        impl<'de> Deserialize<'de> for PaletteKey {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<PaletteKey, D::Error> {
+                struct PaletteKeyVisitor;
+
+                impl Visitor<'_> for PaletteKeyVisitor {
+                    type Value = PaletteKey;
+
+                    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                        f.write_str("the key \"palette\"")
+                    }
+
+                    fn visit_str<E: de::Error>(self, v: &str) -> Result<PaletteKey, E> {
+                        match v {
+                            "palette"   => Ok(PaletteKey),
+                            _           => Err(E::unknown_field(v, &["palette"])),
+                        }
+                    }
+                }
+
+                deserializer.deserialize_identifier(PaletteKeyVisitor)
+            }
+        }
+
+        struct ColorVisitor;
+
+        impl<'de> Visitor<'de> for ColorVisitor {
+            type Value = Color;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a color name, a hex color such as \"#ff2d44\", or { \"palette\": n }")
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<Color, E> {
+                if v.starts_with('#') {
+                    let (r, g, b) = parse_hex(v).map_err(E::custom)?;
+                    Ok(Color::Rgb(r, g, b))
+                } else {
+                    NamedColor::from_name(v).map(Color::Named).ok_or_else(|| E::invalid_value(Unexpected::Str(v), &self))
+                }
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Color, A::Error> {
+                let Some(PaletteKey) = map.next_key()? else {
+                    return Err(de::Error::missing_field("palette"));
+                };
+                let n = map.next_value()?;
+                if map.next_key::<PaletteKey>()?.is_some() {
+                    return Err(de::Error::duplicate_field("palette"));
+                }
+                Ok(Color::Palette(n))
+            }
+        }
+
+        deserializer.deserialize_any(ColorVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let colors = [
+            Color::Named(NamedColor::Red),
+            Color::Named(NamedColor::BrightBlack),
+            Color::Palette(214),
+            Color::Rgb(255, 45, 68),
+            Color::Rgb(0, 1, 2),
+        ];
+        let json = r##"["red","bright black",{"palette":214},"#ff2d44","#000102"]"##;
+
+        assert_eq!(serde_json::to_string(&colors).unwrap(), json);
+        assert_eq!(serde_json::from_str::<[Color; 5]>(json).unwrap(), colors);
+
+        for &color in NamedColor::all() {
+            let json = serde_json::to_string(&color).unwrap();
+            assert_eq!(serde_json::from_str::<NamedColor>(&json).unwrap(), color);
+        }
+    }
+
+    #[test]
+    fn lenient_forms() {
+        let parse = |json: &str| serde_json::from_str::<Color>(json).ok();
+
+        assert_eq!(parse(r#""Bright_Blue""#),   Some(Color::Named(NamedColor::BrightBlue)));
+        assert_eq!(parse(r#""bright-cyan""#),   Some(Color::Named(NamedColor::BrightCyan)));
+        assert_eq!(parse(r##""#F2A""##),        Some(Color::Rgb(0xff, 0x22, 0xaa)));
+        assert_eq!(parse(r#""purple""#),        None);
+        assert_eq!(parse(r##""#ff2d4""##),      None);
+        assert_eq!(parse(r#""ff2d44""#),        None);
+        assert_eq!(parse(r#"{"palette":256}"#), None);
+        assert_eq!(parse(r#"{"rgb":1}"#),       None);
+        assert_eq!(parse(r#"{}"#),              None);
+        assert_eq!(parse("3"),                  None);
+    }
+}
@@ -0,0 +1,677 @@
+// Parsing SGR ("Select Graphic Rendition") control codes, the kind this crate emits.
+
+use core::fmt;
+
+#[cfg(feature = "alloc")]
+use alloc::{string::String, vec::Vec};
+
+use crate::{Color, NamedColor};
+use crate::scan::{sgr_params, tokens, Token};
+#[cfg(feature = "alloc")]
+use crate::Style;
+
+/// One parameter of an SGR control code, as returned by `parse_sgr`. Each variant notes the
+/// parameter number(s) it corresponds to and the constant of this crate that emits it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SgrParam {
+    /// 0 (`RESET`). An empty parameter list (`\x1b[m`) also means this.
+    Reset,
+    /// 1 (`BOLD`).
+    Bold,
+    /// 2 (`DIM`).
+    Dim,
+    /// 3 (`ITALIC`).
+    Italic,
+    /// 4 (`UNDERLINE`).
+    Underline,
+    /// 5 (`SLOW_BLINK`).
+    SlowBlink,
+    /// 6 (`RAPID_BLINK`).
+    RapidBlink,
+    /// 7 (`INVERTED`).
+    Inverted,
+    /// 8 (`HIDDEN`).
+    Hidden,
+    /// 9 (`STRIKETHROUGH`).
+    Strikethrough,
+    /// 10–19 (`DEFAULT_FONT` and `ALT_FONT_1` through `ALT_FONT_9`). The number is 0 for the default
+    /// font and 1–9 for the alternative fonts.
+    Font(u8),
+    /// 20 (`FRAKTUR`).
+    Fraktur,
+    /// 21 (`DOUBLE_UNDERLINE`, and also `NOT_BOLD`). This parameter is parsed as a double underline,
+    /// which is what the standard says it means, even though some terminals treat it as "not bold".
+    DoubleUnderline,
+    /// 22 (`NORMAL_INTENSITY`).
+    NormalIntensity,
+    /// 23 (`NEITHER_BOLD_NOR_ITALIC`). Most terminals treat this as "not italic" and leave bold
+    /// text bold.
+    NotItalic,
+    /// 24 (`NOT_UNDERLINED`).
+    NotUnderlined,
+    /// 25 (`NOT_BLINKING`).
+    NotBlinking,
+    /// 26 (`PROPORTIONAL_SPACING`).
+    ProportionalSpacing,
+    /// 27 (`NOT_INVERTED`).
+    NotInverted,
+    /// 28 (`NOT_HIDDEN`).
+    NotHidden,
+    /// 29 (`NOT_STRIKETHROUGH`).
+    NotStrikethrough,
+    /// 30–37 and 90–97 (`BLACK`, `BRIGHT_BLACK`, ...), `38;5;n` (`color_256`), and `38;2;r;g;b`
+    /// (`truecolor`).
+    Foreground(Color),
+    /// 39 (`DEFAULT`).
+    DefaultForeground,
+    /// 40–47 and 100–107 (`BLACK_BG`, `BRIGHT_BLACK_BG`, ...), `48;5;n` (`color_256_bg`), and
+    /// `48;2;r;g;b` (`truecolor_bg`).
+    Background(Color),
+    /// 49 (`DEFAULT_BG`).
+    DefaultBackground,
+    /// 50 (`NO_PROPORTIONAL_SPACING`).
+    NoProportionalSpacing,
+    /// 51 (`FRAMED`).
+    Framed,
+    /// 52 (`ENCIRCLED`).
+    Encircled,
+    /// 53 (`OVERLINE`).
+    Overline,
+    /// 54 (`NEITHER_FRAMED_NOR_ENCIRCLED`).
+    NeitherFramedNorEncircled,
+    /// 55 (`NOT_OVERLINED`).
+    NotOverlined,
+    /// `58;5;n` (`underline_color_256`) and `58;2;r;g;b` (`underline_color`).
+    UnderlineColor(Color),
+    /// 59 (`DEFAULT_UNDERLINE_COLOR`).
+    DefaultUnderlineColor,
+    /// A parameter this crate doesn't recognize. An extended color (38, 48, or 58) whose other
+    /// parameters are missing or out of range is also reported as unknown, with the number 38,
+    /// 48, or 58.
+    Unknown(u16),
+}
+
+/// Displays as the parameter number(s), such as `1` for `SgrParam::Bold` or `38;5;214` for
+/// `SgrParam::Foreground(Color::Palette(214))`, without the surrounding `\x1b[` and `m`.
+impl fmt::Display for SgrParam {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let n = match *self {
+            SgrParam::Reset                     => 0,
+            SgrParam::Bold                      => 1,
+            SgrParam::Dim                       => 2,
+            SgrParam::Italic                    => 3,
+            SgrParam::Underline                 => 4,
+            SgrParam::SlowBlink                 => 5,
+            SgrParam::RapidBlink                => 6,
+            SgrParam::Inverted                  => 7,
+            SgrParam::Hidden                    => 8,
+            SgrParam::Strikethrough             => 9,
+            SgrParam::Font(n)                   => 10 + n as u16,
+            SgrParam::Fraktur                   => 20,
+            SgrParam::DoubleUnderline           => 21,
+            SgrParam::NormalIntensity           => 22,
+            SgrParam::NotItalic                 => 23,
+            SgrParam::NotUnderlined             => 24,
+            SgrParam::NotBlinking               => 25,
+            SgrParam::ProportionalSpacing       => 26,
+            SgrParam::NotInverted               => 27,
+            SgrParam::NotHidden                 => 28,
+            SgrParam::NotStrikethrough          => 29,
+            SgrParam::Foreground(color)         => return color.write_params(f, false),
+            SgrParam::DefaultForeground         => 39,
+            SgrParam::Background(color)         => return color.write_params(f, true),
+            SgrParam::DefaultBackground         => 49,
+            SgrParam::NoProportionalSpacing     => 50,
+            SgrParam::Framed                    => 51,
+            SgrParam::Encircled                 => 52,
+            SgrParam::Overline                  => 53,
+            SgrParam::NeitherFramedNorEncircled => 54,
+            SgrParam::NotOverlined              => 55,
+            SgrParam::UnderlineColor(color)     => return color.write_underline_params(f),
+            SgrParam::DefaultUnderlineColor     => 59,
+            SgrParam::Unknown(n)                => n,
+        };
+        write!(f, "{n}")
+    }
+}
+
+/// The error returned by `parse_sgr` when its input isn't an SGR control code.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParseSgrError {
+    /// The input didn't start with `\x1b[` and end with `m`.
+    NotSgr,
+    /// The parameters contained this character, which is neither a digit nor a `;`.
+    InvalidCharacter(char),
+}
+
+impl fmt::Display for ParseSgrError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseSgrError::NotSgr               => write!(f, "not an SGR control code"),
+            ParseSgrError::InvalidCharacter(c)  => write!(f, "invalid character {c:?} in SGR parameters"),
+        }
+    }
+}
+
+impl core::error::Error for ParseSgrError {}
+
+/// Parse the parameters of an extended color (the part after 38, 48, or 58), consuming them from
+/// `params`.
+fn parse_extended_color(params: &mut impl Iterator<Item = u16>) -> Option<Color> {
+    let channel = |p: Option<u16>| p.and_then(|p| u8::try_from(p).ok());
+
+    match params.next()? {
+        5 => Some(Color::Palette(channel(params.next())?)),
+        2 => {
+            let (r, g, b) = (channel(params.next()), channel(params.next()), channel(params.next()));
+            Some(Color::Rgb(r?, g?, b?))
+        }
+        _ => None,
+    }
+}
+
+/// Parse the parameter list of an SGR control code (the part between `\x1b[` and `m`), returning
+/// an iterator over the parameters.
+pub(crate) fn parse_sgr_params(params: &str) -> Result<impl Iterator<Item = SgrParam> + '_, ParseSgrError> {
+    if let Some(c) = params.chars().find(|&c| c != ';' && !c.is_ascii_digit()) {
+        return Err(ParseSgrError::InvalidCharacter(c));
+    }
+
+    let mut numbers = params
+        .split(';')
+        .map(|p| p.parse::<u32>().map_or(0, |n| n.min(u16::MAX as u32) as u16));
+
+    Ok(core::iter::from_fn(move || {
+        let n = numbers.next()?;
+        let named = |offset: u16| NamedColor::from_index((n - offset) as u8).map(Color::Named).unwrap();
+
+        Some(match n {
+            0           => SgrParam::Reset,
+            1           => SgrParam::Bold,
+            2           => SgrParam::Dim,
+            3           => SgrParam::Italic,
+            4           => SgrParam::Underline,
+            5           => SgrParam::SlowBlink,
+            6           => SgrParam::RapidBlink,
+            7           => SgrParam::Inverted,
+            8           => SgrParam::Hidden,
+            9           => SgrParam::Strikethrough,
+            10..=19     => SgrParam::Font((n - 10) as u8),
+            20          => SgrParam::Fraktur,
+            21          => SgrParam::DoubleUnderline,
+            22          => SgrParam::NormalIntensity,
+            23          => SgrParam::NotItalic,
+            24          => SgrParam::NotUnderlined,
+            25          => SgrParam::NotBlinking,
+            26          => SgrParam::ProportionalSpacing,
+            27          => SgrParam::NotInverted,
+            28          => SgrParam::NotHidden,
+            29          => SgrParam::NotStrikethrough,
+            30..=37     => SgrParam::Foreground(named(30)),
+            38          => parse_extended_color(&mut numbers).map_or(SgrParam::Unknown(38), SgrParam::Foreground),
+            39          => SgrParam::DefaultForeground,
+            40..=47     => SgrParam::Background(named(40)),
+            48          => parse_extended_color(&mut numbers).map_or(SgrParam::Unknown(48), SgrParam::Background),
+            49          => SgrParam::DefaultBackground,
+            50          => SgrParam::NoProportionalSpacing,
+            51          => SgrParam::Framed,
+            52          => SgrParam::Encircled,
+            53          => SgrParam::Overline,
+            54          => SgrParam::NeitherFramedNorEncircled,
+            55          => SgrParam::NotOverlined,
+            58          => parse_extended_color(&mut numbers).map_or(SgrParam::Unknown(58), SgrParam::UnderlineColor),
+            59          => SgrParam::DefaultUnderlineColor,
+            90..=97     => SgrParam::Foreground(named(82)),
+            100..=107   => SgrParam::Background(named(92)),
+            n           => SgrParam::Unknown(n),
+        })
+    }))
+}
+
+/// Parse an SGR control code (such as `\x1b[1;31m`, or any of the constants of this crate) into
+/// its parameters. This is the inverse of the constants and color functions: for example,
+/// `parse_sgr(RED)` returns `[SgrParam::Foreground(Color::Named(NamedColor::Red))]`, and
+/// `parse_sgr(&truecolor(1, 2, 3))` returns `[SgrParam::Foreground(Color::Rgb(1, 2, 3))]`.
+///
+/// Parameters this crate doesn't recognize are returned as `SgrParam::Unknown`. An error is only
+/// returned if the input isn't a single SGR control code at all.
+///
+/// ```
+/// use flower_pot::*;
+///
+/// let params = parse_sgr("\x1b[1;38;5;214m").unwrap();
+/// assert_eq!(params, [SgrParam::Bold, SgrParam::Foreground(Color::Palette(214))]);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn parse_sgr(seq: &str) -> Result<Vec<SgrParam>, ParseSgrError> {
+    let params = seq
+        .strip_prefix("\x1b[")
+        .and_then(|s| s.strip_suffix('m'))
+        .ok_or(ParseSgrError::NotSgr)?;
+    Ok(parse_sgr_params(params)?.collect())
+}
+
+/// Merge several SGR control codes (such as the constants of this crate and the strings returned
+/// by its color functions) into a single control code with the same effect, to send fewer bytes to
+/// the terminal. The inputs may themselves have several parameters. A reset (`RESET`, or a `0`
+/// parameter) cancels the parameters before it, so those are left out of the result, but the
+/// reset itself is kept. Strings that aren't SGR control codes are ignored, and if no parameters
+/// are left, the empty string is returned.
+///
+/// ```
+/// use flower_pot::*;
+///
+/// assert_eq!(combine(&[BOLD, &truecolor(255, 45, 68)]), "\x1b[1;38;2;255;45;68m");
+/// assert_eq!(combine(&[ITALIC, RESET, GREEN_BG]), "\x1b[0;42m");
+/// ```
+#[cfg(feature = "alloc")]
+pub fn combine(codes: &[&str]) -> String {
+    use core::fmt::Write;
+
+    let mut params = Vec::new();
+    for code in codes {
+        let Ok(parsed) = parse_sgr(code) else { continue };
+        for param in parsed {
+            if param == SgrParam::Reset {
+                params.clear();
+            }
+            params.push(param);
+        }
+    }
+
+    let mut out = String::new();
+    for (i, param) in params.iter().enumerate() {
+        let _ = write!(out, "{}{param}", if i == 0 { "\x1b[" } else { ";" });
+    }
+    if !out.is_empty() {
+        out.push('m');
+    }
+    out
+}
+
+/// Rewrite a string so that its SGR control codes are in a standard form, for deduplicating styled
+/// strings or for snapshot tests that shouldn't depend on how the styling was put together. Each
+/// run of SGR control codes with no text between them is replaced by the shortest single control
+/// code that has the same effect on the style of the text after it (as `Style::diff` would
+/// compute it), or removed if it has no effect, so strings that display the same way are
+/// canonicalized to the same string. Text and other control codes are kept exactly as they are.
+///
+/// Parameters that a `Style` can't represent (such as `FRAKTUR`) are kept, in their original
+/// order, after the parameters that it can, unless a later reset in the same run cancels them. So
+/// are the parameters that turn them off (such as `NOT_UNDERLINED` after `DOUBLE_UNDERLINE`).
+/// Malformed SGR control codes are kept as they are, like other control codes.
+///
+/// ```
+/// use flower_pot::*;
+///
+/// let a = format!("{BOLD}{RED}error{RESET}");
+/// let b = format!("{RED}\x1b[1m{GREEN}{RED}error{NORMAL_INTENSITY}{DEFAULT}");
+/// assert_eq!(canonicalize(&a), "\x1b[1;31merror\x1b[0m");
+/// assert_eq!(canonicalize(&a), canonicalize(&b));
+/// ```
+#[cfg(feature = "alloc")]
+pub fn canonicalize(input: &str) -> String {
+    use core::fmt::Write;
+
+    // `current` is the style that the output so far leaves the terminal in, and `pending` is the
+    // style that the run of control codes being read so far changes it to. `active` holds the
+    // parameters that a `Style` can't represent (such as `FRAKTUR`) that the output so far has
+    // turned on and not yet turned off.
+    struct State {
+        current:        Style,
+        pending:        Style,
+        leading:        Vec<SgrParam>,
+        extra:          Vec<SgrParam>,
+        active:         Vec<SgrParam>,
+        reset:          bool,
+        extra_active:   bool,
+    }
+
+    /// The parameter that a `Style` can't represent that `param` turns off, if any.
+    fn turned_off_by(param: SgrParam) -> Option<SgrParam> {
+        match param {
+            SgrParam::NotItalic                             => Some(SgrParam::Fraktur),
+            SgrParam::NotUnderlined | SgrParam::Underline   => Some(SgrParam::DoubleUnderline),
+            SgrParam::NotBlinking | SgrParam::SlowBlink     => Some(SgrParam::RapidBlink),
+            _                                               => None,
+        }
+    }
+
+    impl State {
+        /// Read a parameter of the run of control codes.
+        fn push(&mut self, param: SgrParam) {
+            // A parameter that turns off an attribute which only the extra parameters can turn on
+            // cancels the ones earlier in this run, and has to be kept if the attribute was on
+            // before the run. It goes before everything else, so that it can't turn off anything
+            // turned on later in the run.
+            if let Some(on) = turned_off_by(param) {
+                self.extra.retain(|&p| p != on);
+                let kept = self.leading.iter().any(|&p| turned_off_by(p) == Some(on));
+                if self.active.contains(&on) && !self.reset && !kept {
+                    self.leading.push(param);
+                }
+            }
+
+            if param == SgrParam::Reset {
+                self.leading.clear();
+                self.extra.clear();
+                self.reset = true;
+            }
+            if !self.pending.apply_param(param) {
+                self.extra.push(param);
+            }
+        }
+
+        fn flush(&mut self, out: &mut String) {
+            let mut from = self.current;
+            for &param in &self.leading {
+                from.apply_param(param);
+            }
+
+            let mut incremental = String::new();
+            let _ = from.write_change(&self.pending, false, &self.extra, &mut incremental);
+            if !self.leading.is_empty() {
+                let rest = incremental.strip_prefix("\x1b[").unwrap_or("m");
+                let mut code = String::new();
+                for param in &self.leading {
+                    let _ = write!(code, "{}{param}", if code.is_empty() { "\x1b[" } else { ";" });
+                }
+                let _ = write!(code, "{}{rest}", if rest == "m" { "" } else { ";" });
+                incremental = code;
+            }
+
+            // Parameters that a `Style` can't represent can only be turned off by a reset, and
+            // are only known to be off if there has been one since they were turned on.
+            let code = if self.extra_active && !self.reset {
+                incremental
+            } else {
+                let mut reset = String::new();
+                let _ = self.current.write_change(&self.pending, true, &self.extra, &mut reset);
+                if self.extra_active || reset.len() < incremental.len() {
+                    self.extra_active = false;
+                    self.active.clear();
+                    reset
+                } else {
+                    incremental
+                }
+            };
+
+            out.push_str(&code);
+            for param in self.leading.drain(..) {
+                self.active.retain(|&p| Some(p) != turned_off_by(param));
+            }
+            for &param in &self.extra {
+                let turned_off = [SgrParam::Fraktur, SgrParam::DoubleUnderline, SgrParam::RapidBlink];
+                if turned_off.contains(&param) && !self.active.contains(&param) {
+                    self.active.push(param);
+                }
+            }
+            self.extra_active |= !self.extra.is_empty();
+            self.current = self.pending;
+            self.extra.clear();
+            self.reset = false;
+        }
+
+        /// Pass on a control code or some text unchanged.
+        fn pass(&mut self, token: Token, out: &mut String) {
+            self.flush(out);
+            let (Token::Escape(s) | Token::Text(s)) = token;
+            out.push_str(s);
+
+            // An SGR control code that couldn't be parsed has an unknown effect, except that an
+            // underline style (`4:` followed by a number) other than 0 turns on an underline, which
+            // is turned off the same way as `DOUBLE_UNDERLINE`.
+            if let Token::Escape(escape) = token && let Some(params) = sgr_params(escape) {
+                self.extra_active = true;
+                for param in params.split(';').filter_map(|p| p.strip_prefix("4:")) {
+                    self.active.retain(|&p| p != SgrParam::DoubleUnderline);
+                    if !param.trim_start_matches('0').is_empty() {
+                        self.active.push(SgrParam::DoubleUnderline);
+                    }
+                }
+            }
+        }
+    }
+
+    let mut out = String::new();
+    let mut state = State {
+        current:        Style::new(),
+        pending:        Style::new(),
+        leading:        Vec::new(),
+        extra:          Vec::new(),
+        active:         Vec::new(),
+        reset:          false,
+        extra_active:   false,
+    };
+
+    for token in tokens(input) {
+        let params = match token {
+            Token::Escape(escape)   => sgr_params(escape).map(parse_sgr_params),
+            Token::Text(_)          => None,
+        };
+
+        let Some(Ok(params)) = params else {
+            state.pass(token, &mut out);
+            continue;
+        };
+
+        for param in params {
+            state.push(param);
+        }
+    }
+
+    state.flush(&mut out);
+    out
+}
+
+/// How many SGR control codes a string contains, and what kinds of parameters they have. Returned
+/// by `count_styles`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct StyleStats {
+    /// The number of SGR control codes.
+    pub sequences: usize,
+    /// The number of parameters that set the foreground color, including `DEFAULT`.
+    pub foreground: usize,
+    /// The number of parameters that set the background color, including `DEFAULT_BG`.
+    pub background: usize,
+    /// The number of parameters that turn a text attribute (such as bold or underline) on or off.
+    pub attributes: usize,
+    /// The number of resets (`RESET`, a `0` parameter, or an empty parameter list).
+    pub resets: usize,
+    /// The number of other parameters: underline colors and parameters this crate doesn't
+    /// recognize.
+    pub other: usize,
+}
+
+/// Count the SGR control codes in a string and their parameters by kind, for finding out why some
+/// output is bigger or slower to render than it should be. Only SGR control codes are counted;
+/// text, other control codes, and malformed SGR codes are skipped. Nothing is allocated, so this
+/// is cheap to run on large buffers.
+///
+/// ```
+/// use flower_pot::*;
+/// # #[cfg(feature = "alloc")] {
+///
+/// let stats = count_styles(&format!("{BOLD}{RED}a{RED}b{RESET}{}c", color_256_bg(4)));
+/// assert_eq!(stats.sequences, 5);
+/// assert_eq!((stats.foreground, stats.background), (2, 1));
+/// assert_eq!((stats.attributes, stats.resets), (1, 1));
+/// # }
+/// ```
+pub fn count_styles(input: &str) -> StyleStats {
+    let mut stats = StyleStats::default();
+    for token in tokens(input) {
+        let Token::Escape(escape) = token else { continue };
+        let Some(Ok(params)) = sgr_params(escape).map(parse_sgr_params) else { continue };
+
+        stats.sequences += 1;
+        for param in params {
+            let count = match param {
+                SgrParam::Reset                                                 => &mut stats.resets,
+                SgrParam::Foreground(_) | SgrParam::DefaultForeground           => &mut stats.foreground,
+                SgrParam::Background(_) | SgrParam::DefaultBackground           => &mut stats.background,
+                SgrParam::UnderlineColor(_) | SgrParam::DefaultUnderlineColor   => &mut stats.other,
+                SgrParam::Unknown(_)                                            => &mut stats.other,
+                _                                                               => &mut stats.attributes,
+            };
+            *count += 1;
+        }
+    }
+    stats
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use crate::*;
+
+    fn parse_one(seq: &str) -> SgrParam {
+        let params = parse_sgr(seq).unwrap();
+        assert_eq!(params.len(), 1, "{seq:?}");
+        params[0]
+    }
+
+    #[test]
+    fn round_trip_constants() {
+        assert_eq!(parse_one(RESET),            SgrParam::Reset);
+        assert_eq!(parse_one(BOLD),             SgrParam::Bold);
+        assert_eq!(parse_one(ALT_FONT_3),       SgrParam::Font(3));
+        assert_eq!(parse_one(NOT_BOLD),         SgrParam::DoubleUnderline);
+        assert_eq!(parse_one(DEFAULT),          SgrParam::DefaultForeground);
+        assert_eq!(parse_one(NOT_OVERLINED),    SgrParam::NotOverlined);
+        assert_eq!(parse_one(RED),              SgrParam::Foreground(Color::Named(NamedColor::Red)));
+        assert_eq!(parse_one(WHITE_BG),         SgrParam::Background(Color::Named(NamedColor::White)));
+        assert_eq!(parse_one(BRIGHT_BLACK),     SgrParam::Foreground(Color::Named(NamedColor::BrightBlack)));
+        assert_eq!(parse_one(BRIGHT_WHITE_BG),  SgrParam::Background(Color::Named(NamedColor::BrightWhite)));
+    }
+
+    #[test]
+    fn round_trip_functions() {
+        for n in 0..=255 {
+            assert_eq!(parse_one(&color_256(n)),    SgrParam::Foreground(Color::Palette(n)));
+            assert_eq!(parse_one(&color_256_bg(n)), SgrParam::Background(Color::Palette(n)));
+        }
+        assert_eq!(parse_one(&truecolor(127, 45, 68)),  SgrParam::Foreground(Color::Rgb(127, 45, 68)));
+        assert_eq!(parse_one(&truecolor_bg(0, 255, 9)), SgrParam::Background(Color::Rgb(0, 255, 9)));
+    }
+
+    #[test]
+    fn multiple_params() {
+        let style = Style::new().bold().underline().fg(Color::Rgb(1, 2, 3)).bg(Color::Palette(4));
+        assert_eq!(parse_sgr(&style.to_string()).unwrap(), [
+            SgrParam::Bold,
+            SgrParam::Underline,
+            SgrParam::Foreground(Color::Rgb(1, 2, 3)),
+            SgrParam::Background(Color::Palette(4)),
+        ]);
+        assert_eq!(parse_sgr("\x1b[m").unwrap(), [SgrParam::Reset]);
+        assert_eq!(parse_sgr("\x1b[;1m").unwrap(), [SgrParam::Reset, SgrParam::Bold]);
+    }
+
+    #[test]
+    fn unknown_and_malformed() {
+        assert_eq!(parse_sgr("\x1b[56;1m").unwrap(),        [SgrParam::Unknown(56), SgrParam::Bold]);
+        assert_eq!(parse_sgr("\x1b[38;5m").unwrap(),        [SgrParam::Unknown(38)]);
+        assert_eq!(parse_sgr("\x1b[48;5;256m").unwrap(),    [SgrParam::Unknown(48)]);
+        assert_eq!(parse_sgr("\x1b[38;2;1;2m").unwrap(),    [SgrParam::Unknown(38)]);
+        assert_eq!(parse_sgr("\x1b[38;9;1m").unwrap(),      [SgrParam::Unknown(38), SgrParam::Bold]);
+        assert_eq!(parse_sgr("\x1b[99999m").unwrap(),       [SgrParam::Unknown(u16::MAX)]);
+        assert_eq!(parse_sgr("\x1b[1"),                     Err(ParseSgrError::NotSgr));
+        assert_eq!(parse_sgr("[1m"),                        Err(ParseSgrError::NotSgr));
+        assert_eq!(parse_sgr("\x1b[4:3m"),                  Err(ParseSgrError::InvalidCharacter(':')));
+    }
+
+    #[test]
+    fn params_display_round_trip() {
+        let seq = "\x1b[0;1;2;3;4;5;6;7;8;9;10;19;20;21;22;23;24;25;26;27;28;29;30;97;38;5;214;38;2;1;2;3;39;\
+                   40;107;48;5;7;48;2;4;5;6;49;50;51;52;53;54;55;58;5;9;58;2;7;8;9;59;56m";
+        let seq: String = seq.split_whitespace().collect();
+        let params = parse_sgr(&seq).unwrap();
+        let displayed: Vec<String> = params.iter().map(|p| p.to_string()).collect();
+        assert_eq!(format!("\x1b[{}m", displayed.join(";")), seq);
+    }
+
+    #[test]
+    fn combine_merges() {
+        assert_eq!(combine(&[]), "");
+        assert_eq!(combine(&[BOLD]), BOLD);
+        assert_eq!(combine(&[BOLD, UNDERLINE, &color_256_bg(3)]), "\x1b[1;4;48;5;3m");
+        assert_eq!(combine(&["\x1b[1;2m", "\x1b[3;38;5;0m"]), "\x1b[1;2;3;38;5;0m");
+        assert_eq!(combine(&[BOLD, "not a code", RED]), "\x1b[1;31m");
+    }
+
+    #[test]
+    fn combine_resets() {
+        assert_eq!(combine(&[BOLD, RESET]), RESET);
+        assert_eq!(combine(&[BOLD, "\x1b[3;0;4m", RED]), "\x1b[0;4;31m");
+        assert_eq!(combine(&[BOLD, "\x1b[m", RED]), "\x1b[0;31m");
+    }
+
+    #[test]
+    fn canonical_runs() {
+        assert_eq!(canonicalize("\x1b[1m\x1b[31mx"),                           "\x1b[1;31mx");
+        assert_eq!(canonicalize(&format!("{RED}a{GREEN}{RED}b{RESET}")),      format!("{RED}ab{RESET}"));
+        assert_eq!(canonicalize(&format!("{BOLD}{RED}a{NORMAL_INTENSITY}b")),  format!("\x1b[1;31ma{NORMAL_INTENSITY}b"));
+        assert_eq!(canonicalize(&format!("a{ITALIC}{NEITHER_BOLD_NOR_ITALIC}b")),       "ab");
+        assert_eq!(canonicalize(""),                                           "");
+    }
+
+    #[test]
+    fn canonical_keeps_the_rest() {
+        let link = hyperlink("url", "x");
+        assert_eq!(canonicalize(&format!("{BOLD}{link}{RED}y")),               format!("{BOLD}{link}{RED}y"));
+        assert_eq!(canonicalize("\x1b[4:3ma\x1b[2"),                           "\x1b[4:3ma\x1b[2");
+
+        assert_eq!(canonicalize(&format!("{FRAKTUR}{BOLD}a")),                 "\x1b[1;20ma");
+        assert_eq!(canonicalize(&format!("{FRAKTUR}{RESET}{BOLD}a")),          format!("{BOLD}a"));
+        assert_eq!(canonicalize(&format!("{FRAKTUR}a{RESET}b")),               format!("{FRAKTUR}a{RESET}b"));
+        assert_eq!(canonicalize(&format!("{FRAKTUR}{RED}a{RESET}{RED}b")),     "\x1b[31;20ma\x1b[0;31mb");
+        assert_eq!(canonicalize(&format!("{FRAKTUR}{RED}a{DEFAULT}b")),        format!("\x1b[31;20ma{DEFAULT}b"));
+
+        assert_eq!(canonicalize("\x1b[21ma\x1b[24mb"),                          "\x1b[21ma\x1b[24mb");
+        assert_eq!(canonicalize("\x1b[4:3ma\x1b[24mb"),                         "\x1b[4:3ma\x1b[24mb");
+        assert_eq!(canonicalize("\x1b[20ma\x1b[23mb"),                          "\x1b[20ma\x1b[23mb");
+        assert_eq!(canonicalize("\x1b[21ma\x1b[24;4mb"),                        "\x1b[21ma\x1b[24;4mb");
+        assert_eq!(canonicalize("\x1b[20;23ma\x1b[23mb"),                       "ab");
+        assert_eq!(canonicalize("\x1b[21;4ma"),                                 "\x1b[4ma");
+
+        assert_eq!(canonicalize("\x1b[6mx"),                                    "\x1b[6mx");
+        assert_eq!(canonicalize("\x1b[6ma\x1b[25mb"),                           "\x1b[6ma\x1b[25mb");
+        assert_eq!(canonicalize("\x1b[6;5ma"),                                  "\x1b[5ma");
+        assert_eq!(canonicalize("\x1b[5;6ma\x1b[5mb"),                          "\x1b[5;6ma\x1b[5mb");
+    }
+
+    #[test]
+    fn canonical_round_trip() {
+        let inputs = [
+            format!("{BOLD}{DIM}a{NORMAL_INTENSITY}{DIM}b{RESET}c"),
+            format!("{}{RED_BG}a{BLUE}b{DEFAULT_BG}c{RESET}", truecolor(1, 2, 3)),
+            format!("{ITALIC}{UNDERLINE}a{NOT_UNDERLINED}b{RESET}{ITALIC}c"),
+        ];
+
+        for input in inputs {
+            let canonical = canonicalize(&input);
+            assert_eq!(spans(&canonical), spans(&input));
+            assert_eq!(canonicalize(&canonical), canonical);
+            assert!(canonical.len() <= input.len());
+        }
+    }
+
+    #[test]
+    fn style_counts() {
+        let input = format!(
+            "{BOLD}{ITALIC}a\x1b[1;32;44mb{NOT_BOLD}{DEFAULT}{DEFAULT_BG}\x1b[mc{}{}{}\x1b[99m\x1b[1x{RESET}",
+            truecolor(1, 2, 3), underline_color_256(5), hyperlink("url", "d"),
+        );
+        assert_eq!(count_styles(&input), StyleStats {
+            sequences:  11,
+            foreground: 3,
+            background: 2,
+            attributes: 4,
+            resets:     2,
+            other:      2,
+        });
+        assert_eq!(count_styles("plain \x1b[2J text"), StyleStats::default());
+        assert_eq!(count_styles(""), StyleStats::default());
+    }
+}
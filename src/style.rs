@@ -0,0 +1,967 @@
+// Styles as values.
+
+use core::fmt;
+use core::ops::{Add, AddAssign};
+
+#[cfg(feature = "alloc")]
+use alloc::string::{String, ToString};
+
+use crate::flags::{reset_param, FLAG_PARAMS};
+use crate::config::{write_gated, write_reset};
+#[cfg(feature = "alloc")]
+use crate::html::write_css;
+use crate::scan::{sgr_params, tokens, Token};
+use crate::sgr::parse_sgr_params;
+use crate::{Attribute, Color, ParseSgrError, SgrParam, StyleFlags};
+
+/// The attributes that are both turned off by `NORMAL_INTENSITY`.
+const INTENSITY: StyleFlags = StyleFlags::BOLD.union(StyleFlags::DIM);
+
+/// The attributes that are both turned off by `NEITHER_FRAMED_NOR_ENCIRCLED`.
+const FRAME: StyleFlags = StyleFlags::FRAMED.union(StyleFlags::ENCIRCLED);
+
+/// A combination of text attributes and colors that can be displayed as a single control code.
+/// Build one with `Style::new()` and the builder methods:
+///
+/// ```
+/// use flower_pot::*;
+///
+/// let warning = Style::new().bold().fg(Color::Named(NamedColor::Yellow));
+/// assert_eq!(warning.to_string(), "\x1b[1;33m");
+///
+/// println!("{warning}careful!{RESET}");
+/// ```
+///
+/// A style with nothing set (see `Style::is_empty`) displays as the empty string.
+///
+/// Bold and dim are separate attributes, and a style can have both, but terminals only have one
+/// parameter that turns either of them off: 22 (`NORMAL_INTENSITY`), which turns off both. (The
+/// parameter 21, `NOT_BOLD`, means double underline on many terminals, so it can't be used to turn
+/// off just bold.) `Style::diff` accounts for this: to go from bold and dim to just dim, it turns
+/// off both and then turns dim back on. Use `Style::normal_intensity` to turn off both in a style.
+/// Framed and encircled are the same: 54 (`NEITHER_FRAMED_NOR_ENCIRCLED`) is the only parameter
+/// that turns either of them off, so `Style::diff` turns off both and turns the other back on.
+///
+/// Styles can be compared and hashed (for example, to cache their control codes in a `HashMap`).
+/// A style only stores which attributes are on and which colors are set, not the order in which
+/// they were set, so two styles are equal exactly when they display as the same control code:
+/// `Style::new().bold().italic()` equals `Style::new().italic().bold().bold()`. Colors are
+/// compared as they are given, so `Color::Named(NamedColor::Red)` and `Color::Palette(1)` are
+/// different colors, just as their control codes (`\x1b[31m` and `\x1b[38;5;1m`) are different.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Style {
+    flags:              StyleFlags,
+    pub(crate) fg:      Option<Color>,
+    pub(crate) bg:      Option<Color>,
+}
+
+impl Style {
+    /// Create a style with no attributes or colors set.
+    pub const fn new() -> Style {
+        Style {
+            flags:  StyleFlags::EMPTY,
+            fg:     None,
+            bg:     None,
+        }
+    }
+
+    /// Make the text bold (see `BOLD`).
+    pub const fn bold(self) -> Style { self.add_flags(StyleFlags::BOLD) }
+    /// Make the text dim (see `DIM`).
+    pub const fn dim(self) -> Style { self.add_flags(StyleFlags::DIM) }
+    /// Make the text italic (see `ITALIC`).
+    pub const fn italic(self) -> Style { self.add_flags(StyleFlags::ITALIC) }
+    /// Underline the text (see `UNDERLINE`).
+    pub const fn underline(self) -> Style { self.add_flags(StyleFlags::UNDERLINE) }
+    /// Make the text blink slowly (see `SLOW_BLINK`).
+    pub const fn blink(self) -> Style { self.add_flags(StyleFlags::BLINK) }
+    /// Swap the foreground and background colors (see `INVERTED`).
+    pub const fn inverted(self) -> Style { self.add_flags(StyleFlags::INVERTED) }
+    /// Hide the text (see `HIDDEN`).
+    pub const fn hidden(self) -> Style { self.add_flags(StyleFlags::HIDDEN) }
+    /// Make the text strikethrough (see `STRIKETHROUGH`).
+    pub const fn strikethrough(self) -> Style { self.add_flags(StyleFlags::STRIKETHROUGH) }
+    /// Draw a frame around the text (see `FRAMED`).
+    pub const fn framed(self) -> Style { self.add_flags(StyleFlags::FRAMED) }
+    /// Draw a circle around the text (see `ENCIRCLED`).
+    pub const fn encircled(self) -> Style { self.add_flags(StyleFlags::ENCIRCLED) }
+    /// Draw a line above the text (see `OVERLINE`).
+    pub const fn overline(self) -> Style { self.add_flags(StyleFlags::OVERLINE) }
+
+    /// Turn off both bold and dim, the way `NORMAL_INTENSITY` does.
+    pub const fn normal_intensity(mut self) -> Style { self.flags = self.flags.difference(INTENSITY); self }
+
+    /// Turn on all of the attributes in `flags`, in addition to the ones already on.
+    pub const fn add_flags(mut self, flags: StyleFlags) -> Style { self.flags = self.flags.union(flags); self }
+    /// The attributes that are on in this style.
+    pub const fn flags(&self) -> StyleFlags { self.flags }
+    /// Whether the style has no attributes and no colors set, in which case it displays as the
+    /// empty string.
+    pub const fn is_empty(&self) -> bool { self.flags.is_empty() && self.fg.is_none() && self.bg.is_none() }
+
+    /// Set the foreground color.
+    pub const fn fg(mut self, color: Color) -> Style { self.fg = Some(color); self }
+    /// Set the background color.
+    pub const fn bg(mut self, color: Color) -> Style { self.bg = Some(color); self }
+
+    /// Swap the foreground and background colors of this style, for effects such as highlighting
+    /// a selected item. A color that isn't set stays unset, so after the swap the other layer uses
+    /// the terminal's default color, which may not be the color that was showing before. Unlike
+    /// the `INVERTED` attribute (see `Style::inverted`), which asks the terminal to swap whatever
+    /// colors are in effect and which some terminals don't support, this changes the colors the
+    /// style itself sets, so the result displays, diffs, and combines like any other style. The
+    /// attributes, including `INVERTED`, are left alone.
+    ///
+    /// ```
+    /// use flower_pot::*;
+    /// # #[cfg(feature = "alloc")] {
+    ///
+    /// let normal = Color::Named(NamedColor::White).on(Color::Palette(17));
+    /// let selected = normal.invert();
+    /// assert_eq!(selected, Color::Palette(17).on(Color::Named(NamedColor::White)));
+    /// assert_eq!(normal.diff(&selected), "\x1b[38;5;17;47m");
+    /// # }
+    /// ```
+    pub const fn invert(mut self) -> Style {
+        let fg = self.fg;
+        self.fg = self.bg;
+        self.bg = fg;
+        self
+    }
+
+    /// Keep this style if `cond` is true, or replace it with an empty style (which displays as the
+    /// empty string) if it is false.
+    ///
+    /// ```
+    /// use flower_pot::*;
+    ///
+    /// let interactive = false;
+    /// let error = Style::new().bold().fg(Color::Named(NamedColor::Red)).when(interactive);
+    /// assert_eq!(error.to_string(), "");
+    /// ```
+    pub const fn when(self, cond: bool) -> Style {
+        if cond { self } else { Style::new() }
+    }
+
+    /// Build a style from SGR control codes, such as a style's own control code or codes captured
+    /// from another program's output. The codes are applied one after the other, starting from an
+    /// empty style, the same way as in `apply`: `\x1b[1m\x1b[31m` gives a bold red style, and a
+    /// reset clears everything before it. Parameters that a `Style` can't represent are ignored.
+    /// The empty string gives an empty style, so `Style::parse(&style.to_string())` gives back
+    /// `style` for any style.
+    ///
+    /// An error is returned if `input` contains anything other than SGR control codes, or if one
+    /// of them has an invalid character in its parameters.
+    ///
+    /// ```
+    /// use flower_pot::*;
+    ///
+    /// let red = Color::Named(NamedColor::Red);
+    /// assert_eq!(Style::parse("\x1b[1;31m"), Ok(Style::new().bold().fg(red)));
+    /// assert_eq!(Style::parse(&format!("{ITALIC}{RESET}{RED}")), Ok(Style::new().fg(red)));
+    /// assert_eq!(Style::parse("\x1b[1mbold"), Err(ParseSgrError::NotSgr));
+    /// ```
+    pub fn parse(input: &str) -> Result<Style, ParseSgrError> {
+        let mut style = Style::new();
+        for token in tokens(input) {
+            let Token::Escape(escape) = token else { return Err(ParseSgrError::NotSgr) };
+            for param in parse_sgr_params(sgr_params(escape).ok_or(ParseSgrError::NotSgr)?)? {
+                style.apply_param(param);
+            }
+        }
+        Ok(style)
+    }
+
+    /// Update this style the way a terminal would update its current style on receiving `param`,
+    /// and return whether the parameter is one that a `Style` can represent. Parameters that it
+    /// can't represent are ignored.
+    pub(crate) fn apply_param(&mut self, param: SgrParam) -> bool {
+        match param {
+            SgrParam::Reset                                 => *self = Style::new(),
+            SgrParam::Bold                                  => self.flags |= StyleFlags::BOLD,
+            SgrParam::Dim                                   => self.flags |= StyleFlags::DIM,
+            SgrParam::Italic                                => self.flags |= StyleFlags::ITALIC,
+            SgrParam::Underline                             => self.flags |= StyleFlags::UNDERLINE,
+            SgrParam::SlowBlink                             => self.flags |= StyleFlags::BLINK,
+            SgrParam::Inverted                              => self.flags |= StyleFlags::INVERTED,
+            SgrParam::Hidden                                => self.flags |= StyleFlags::HIDDEN,
+            SgrParam::Strikethrough                         => self.flags |= StyleFlags::STRIKETHROUGH,
+            SgrParam::Framed                                => self.flags |= StyleFlags::FRAMED,
+            SgrParam::Encircled                             => self.flags |= StyleFlags::ENCIRCLED,
+            SgrParam::Overline                              => self.flags |= StyleFlags::OVERLINE,
+            SgrParam::NormalIntensity                       => *self = self.normal_intensity(),
+            SgrParam::NotItalic                             => self.flags = self.flags - StyleFlags::ITALIC,
+            SgrParam::NotUnderlined                         => self.flags = self.flags - StyleFlags::UNDERLINE,
+            SgrParam::NotBlinking                           => self.flags = self.flags - StyleFlags::BLINK,
+            SgrParam::NotInverted                           => self.flags = self.flags - StyleFlags::INVERTED,
+            SgrParam::NotHidden                             => self.flags = self.flags - StyleFlags::HIDDEN,
+            SgrParam::NotStrikethrough                      => self.flags = self.flags - StyleFlags::STRIKETHROUGH,
+            SgrParam::NeitherFramedNorEncircled             => self.flags = self.flags - FRAME,
+            SgrParam::NotOverlined                          => self.flags = self.flags - StyleFlags::OVERLINE,
+            SgrParam::Foreground(color)                     => self.fg = Some(color),
+            SgrParam::DefaultForeground                     => self.fg = None,
+            SgrParam::Background(color)                     => self.bg = Some(color),
+            SgrParam::DefaultBackground                     => self.bg = None,
+            _                                               => return false,
+        }
+        true
+    }
+
+    /// Return the shortest control code that changes the terminal's current style from this style
+    /// to `to`, for renderers that want to move from one style to the next without resetting
+    /// everything in between. Only the attributes and colors that differ are changed; for example,
+    /// changing the foreground color leaves the attributes and the background color alone. If
+    /// resetting and then applying `to` from scratch is shorter, that is returned instead. If the
+    /// styles are the same, the empty string is returned.
+    ///
+    /// ```
+    /// use flower_pot::*;
+    ///
+    /// let plain = Style::new().fg(Color::Named(NamedColor::Red));
+    /// let loud = plain.bold();
+    ///
+    /// assert_eq!(plain.diff(&loud), BOLD);
+    /// assert_eq!(loud.diff(&plain), NORMAL_INTENSITY);
+    /// assert_eq!(loud.diff(&Style::new()), RESET); // shorter than "\x1b[22;39m"
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn diff(&self, to: &Style) -> String {
+        if self == to {
+            return String::new();
+        }
+
+        let mut incremental = String::new();
+        let _ = self.write_change(to, false, &[], &mut incremental);
+
+        let mut reset = String::new();
+        let _ = self.write_change(to, true, &[], &mut reset);
+
+        if reset.len() < incremental.len() { reset } else { incremental }
+    }
+
+    /// The text wrapped in this style and a trailing `RESET`, as a `String`. The style is written as
+    /// a single control code, however many attributes and colors it has. This gives the same result
+    /// as displaying `Styled::new(text, self)` (so it also honors the global switches; see
+    /// `set_colors_enabled`), for when you need the styled text as a value rather than something
+    /// to format.
+    ///
+    /// ```
+    /// use flower_pot::*;
+    ///
+    /// let warning = Style::new().bold().fg(Color::Named(NamedColor::Yellow));
+    /// assert_eq!(warning.apply("careful"), "\x1b[1;33mcareful\x1b[0m");
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn apply(&self, text: &str) -> String {
+        Styled::new(text, self).to_string()
+    }
+
+    /// The CSS declarations that display text the way this style does, separated by `; `, for
+    /// rendering styled text in a web page. The parts of the style are translated the same way
+    /// `to_html` translates them: the foreground and background colors become `color` and
+    /// `background-color` (with named colors given the xterm values listed in `NamedColor::to_rgb`),
+    /// and bold, italic, and underline become `font-weight: bold`, `font-style: italic`, and
+    /// `text-decoration: underline`. The other attributes are left out, and if nothing is left, the
+    /// empty string is returned.
+    ///
+    /// ```
+    /// use flower_pot::*;
+    ///
+    /// let style = Style::new().bold().fg(Color::Rgb(127, 45, 68));
+    /// assert_eq!(style.to_css(), "color: rgb(127, 45, 68); font-weight: bold");
+    /// assert_eq!(Style::new().bg(Color::Named(NamedColor::Blue)).to_css(), "background-color: rgb(0, 0, 238)");
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn to_css(&self) -> String {
+        let mut out = String::new();
+        let _ = write_css(&mut out, self);
+        out
+    }
+
+    /// Write a single control code that changes this style into `to`, either by changing only what
+    /// differs or, if `reset` is true, by resetting and then setting `to` from scratch, followed by
+    /// the parameters in `extra`.
+    pub(crate) fn write_change(&self, to: &Style, reset: bool, extra: &[SgrParam], out: &mut impl fmt::Write) -> fmt::Result {
+        let mut params = ParamWriter::new(out);
+        if reset {
+            write!(params.next()?, "0")?;
+            to.write_params(&mut params)?;
+        } else {
+            self.write_transition(to, &mut params)?;
+        }
+
+        for param in extra {
+            write!(params.next()?, "{param}")?;
+        }
+
+        params.finish()
+    }
+
+    /// Write the SGR parameters that set this style from scratch.
+    fn write_params<W: fmt::Write>(&self, params: &mut ParamWriter<W>) -> fmt::Result {
+        for (flag, on, _) in FLAG_PARAMS {
+            if self.flags.contains(flag) {
+                write!(params.next()?, "{on}")?;
+            }
+        }
+
+        if let Some(color) = self.fg {
+            color.write_params(params.next()?, false)?;
+        }
+
+        if let Some(color) = self.bg {
+            color.write_params(params.next()?, true)?;
+        }
+
+        Ok(())
+    }
+
+    /// Write the SGR parameters that change this style into `to` without a reset.
+    fn write_transition<W: fmt::Write>(&self, to: &Style, params: &mut ParamWriter<W>) -> fmt::Result {
+        let mut from = *self;
+
+        // Bold and dim can only be unset together, as can framed and encircled, so unset both
+        // and then set again whichever one `to` still has.
+        for (pair, attribute) in [(INTENSITY, Attribute::Bold), (FRAME, Attribute::Framed)] {
+            if !((from.flags - to.flags) & pair).is_empty() {
+                write!(params.next()?, "{}", reset_param(attribute))?;
+                from.flags = from.flags - pair;
+            }
+        }
+
+        for (flag, on, off) in FLAG_PARAMS {
+            let (was, set) = (from.flags.contains(flag), to.flags.contains(flag));
+            if set && !was {
+                write!(params.next()?, "{on}")?;
+            } else if was && !set {
+                write!(params.next()?, "{off}")?;
+            }
+        }
+
+        if from.fg != to.fg {
+            match to.fg {
+                Some(color) => color.write_params(params.next()?, false)?,
+                None        => write!(params.next()?, "39")?,
+            }
+        }
+
+        if from.bg != to.bg {
+            match to.bg {
+                Some(color) => color.write_params(params.next()?, true)?,
+                None        => write!(params.next()?, "49")?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Joins SGR parameters into a single control code, writing the `\x1b[`, the semicolons, and the
+/// `m` as needed. Nothing is written if there are no parameters.
+struct ParamWriter<'a, W: fmt::Write> {
+    out:    &'a mut W,
+    empty:  bool,
+}
+
+impl<'a, W: fmt::Write> ParamWriter<'a, W> {
+    fn new(out: &'a mut W) -> ParamWriter<'a, W> {
+        ParamWriter { out, empty: true }
+    }
+
+    /// Start the next parameter and return the writer to write it into.
+    fn next(&mut self) -> Result<&mut W, fmt::Error> {
+        self.out.write_str(if self.empty { "\x1b[" } else { ";" })?;
+        self.empty = false;
+        Ok(self.out)
+    }
+
+    fn finish(self) -> fmt::Result {
+        if self.empty { Ok(()) } else { self.out.write_str("m") }
+    }
+}
+
+/// The longest control code a `Style` can display as: `\x1b[`, every attribute (24 bytes), two
+/// RGB colors (17 bytes each, with their semicolons), and the `m`.
+const MAX_CODE_LEN: usize = 2 + 24 + 17 + 17 + 1;
+
+/// A fixed-size buffer that a style's control code is built up in and then written out in one
+/// piece. This is the hot path of displaying a style, so numbers are formatted by hand rather than
+/// through `write!`, which is much slower for a handful of small integers.
+struct CodeBuf {
+    bytes:  [u8; MAX_CODE_LEN],
+    len:    usize,
+}
+
+impl CodeBuf {
+    const fn new() -> CodeBuf {
+        CodeBuf { bytes: [0; MAX_CODE_LEN], len: 0 }
+    }
+
+    fn push_str(&mut self, s: &str) {
+        self.bytes[self.len..self.len + s.len()].copy_from_slice(s.as_bytes());
+        self.len += s.len();
+    }
+
+    fn push_digit(&mut self, d: u8) {
+        self.bytes[self.len] = b'0' + d;
+        self.len += 1;
+    }
+
+    fn push_u8(&mut self, n: u8) {
+        if n >= 100 {
+            self.push_digit(n / 100);
+        }
+        if n >= 10 {
+            self.push_digit(n / 10 % 10);
+        }
+        self.push_digit(n % 10);
+    }
+
+    /// Start the next parameter, writing the `\x1b[` or the semicolon before it.
+    fn next(&mut self) {
+        self.push_str(if self.len == 0 { "\x1b[" } else { ";" });
+    }
+
+    /// Push the parameters that set `color`, like `Color::write_params`.
+    fn push_color(&mut self, color: Color, background: bool) {
+        self.next();
+        match color {
+            Color::Named(c) if background   => self.push_u8(c.bg_param()),
+            Color::Named(c)                 => self.push_u8(c.fg_param()),
+            Color::Palette(n) => {
+                self.push_str(if background { "48;5;" } else { "38;5;" });
+                self.push_u8(n);
+            }
+            Color::Rgb(r, g, b) => {
+                self.push_str(if background { "48;2;" } else { "38;2;" });
+                self.push_u8(r);
+                self.push_str(";");
+                self.push_u8(g);
+                self.push_str(";");
+                self.push_u8(b);
+            }
+        }
+    }
+
+    /// Add the `m` (unless there are no parameters) and return the control code.
+    fn finish(&mut self) -> Result<&str, fmt::Error> {
+        if self.len > 0 {
+            self.push_str("m");
+        }
+        core::str::from_utf8(&self.bytes[..self.len]).map_err(|_| fmt::Error)
+    }
+}
+
+impl fmt::Display for Style {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // The same parameters as `write_params`, built in a buffer instead.
+        let mut code = CodeBuf::new();
+        for (flag, on, _) in FLAG_PARAMS {
+            if self.flags.contains(flag) {
+                code.next();
+                code.push_u8(on);
+            }
+        }
+
+        if let Some(color) = self.fg {
+            code.push_color(color, false);
+        }
+
+        if let Some(color) = self.bg {
+            code.push_color(color, true);
+        }
+
+        f.write_str(code.finish()?)
+    }
+}
+
+/// Combine two styles: the result has the attributes of both, and the colors of the right-hand
+/// style, except that a color the right-hand style doesn't set is taken from the left-hand style.
+/// This is the style the terminal would be in after the two styles are displayed one after the
+/// other, so the later operands win.
+///
+/// ```
+/// use flower_pot::*;
+///
+/// let red = Style::new().fg(Color::Named(NamedColor::Red));
+/// let on_blue = Style::new().bg(Color::Named(NamedColor::Blue));
+/// let warning = Style::new().bold() + red + on_blue;
+/// assert_eq!(warning.to_string(), "\x1b[1;31;44m");
+///
+/// let green = Style::new().fg(Color::Named(NamedColor::Green));
+/// assert_eq!((warning + green).to_string(), "\x1b[1;32;44m");
+/// ```
+impl Add for Style {
+    type Output = Style;
+
+    fn add(self, other: Style) -> Style {
+        Style {
+            flags:  self.flags.union(other.flags),
+            fg:     other.fg.or(self.fg),
+            bg:     other.bg.or(self.bg),
+        }
+    }
+}
+
+impl AddAssign for Style {
+    fn add_assign(&mut self, other: Style) {
+        *self = *self + other;
+    }
+}
+
+/// Parse a style from SGR control codes (see `Style::parse`).
+impl core::str::FromStr for Style {
+    type Err = ParseSgrError;
+
+    fn from_str(s: &str) -> Result<Style, ParseSgrError> {
+        Style::parse(s)
+    }
+}
+
+/// A value that displays as its content wrapped in a style and a trailing `RESET`, so the style
+/// can't leak into whatever is printed afterwards. The style can be a `Style` or anything else
+/// that displays as a control code, such as the constants of this crate. Create one with
+/// `Styled::new` or the `styled` method of the `Stylize` trait. While colors or text attributes
+/// are turned off (see `set_colors_enabled` and `set_attributes_enabled`), those parts of the style
+/// are left out, and if nothing is left of it, only the content is displayed.
+///
+/// ```
+/// use flower_pot::*;
+///
+/// println!("{}", "ok".styled(GREEN));
+/// println!("{}", 404.styled(Style::new().bold().fg(Color::Named(NamedColor::Red))));
+///
+/// assert_eq!("ok".styled(GREEN).to_string(), format!("{GREEN}ok{RESET}"));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Styled<T, S = Style> {
+    content:    T,
+    style:      S,
+}
+
+impl<T: fmt::Display, S: fmt::Display> Styled<T, S> {
+    /// Wrap `content` in `style`.
+    pub const fn new(content: T, style: S) -> Styled<T, S> {
+        Styled { content, style }
+    }
+}
+
+impl<T: fmt::Display, S: fmt::Display> fmt::Display for Styled<T, S> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let styled = write_gated(f, &self.style)?;
+        write!(f, "{}", self.content)?;
+        if styled {
+            write_reset(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Return `code` if `cond` is true, or the empty string if it is false, so that a control code can
+/// be applied conditionally without an `if` at the call site. For codes returned as a `String` by
+/// functions such as `truecolor`, you can pass a reference, or use `when_owned`.
+///
+/// ```
+/// use flower_pot::*;
+/// # #[cfg(feature = "alloc")] {
+///
+/// let interactive = true;
+/// println!("{}failed{}", when(interactive, RED), when(interactive, RESET));
+/// println!("{}ok{RESET}", when(interactive, &truecolor(0, 200, 0)));
+/// # }
+/// ```
+pub fn when(cond: bool, code: &str) -> &str {
+    if cond { code } else { "" }
+}
+
+/// Like `when`, but takes and returns an owned `String`.
+#[cfg(feature = "alloc")]
+pub fn when_owned(cond: bool, code: String) -> String {
+    if cond { code } else { String::new() }
+}
+
+/// The control code that turns off exactly what `style` turns on, leaving everything else (such as
+/// styling set by surrounding text) alone, as an alternative to the blanket `RESET`. For example,
+/// a style that only sets a foreground color is undone by `DEFAULT` (`\x1b[39m`). Bold and dim
+/// are both turned off by `NORMAL_INTENSITY`, so undoing either one undoes both, and the same goes
+/// for framed and encircled with `NEITHER_FRAMED_NOR_ENCIRCLED`. This is the same
+/// as `style.diff(&Style::new())`, except that it never falls back to `RESET`, even where that
+/// would be shorter. An empty style gives the empty string.
+///
+/// ```
+/// use flower_pot::*;
+///
+/// let style = Style::new().fg(Color::Named(NamedColor::Red));
+/// assert_eq!(reset_for(&style), DEFAULT);
+/// assert_eq!(reset_for(&style.underline().bg(Color::Palette(8))), "\x1b[24;39;49m");
+///
+/// println!("{BOLD}bold, {style}red{} and still bold{RESET}", reset_for(&style));
+/// ```
+#[cfg(feature = "alloc")]
+pub fn reset_for(style: &Style) -> String {
+    let mut out = String::new();
+    let _ = style.write_change(&Style::new(), false, &[], &mut out);
+    out
+}
+
+/// Wrap `content` in `style`, for styling one piece of a larger `format!` or `write!` without
+/// writing the `RESET` yourself. This is the same as `Styled::new(content, style)` and
+/// `content.styled(style)`. Nothing is allocated, and `content` can be borrowed, such as a `&str`.
+///
+/// ```
+/// use flower_pot::*;
+///
+/// let status = format!("status: {}", styled("ok", GREEN));
+/// assert_eq!(status, format!("status: {GREEN}ok{RESET}"));
+///
+/// let red = Style::new().fg(Color::Named(NamedColor::Red));
+/// println!("{} of {} tests failed", styled(3, red), 12);
+/// ```
+pub const fn styled<T: fmt::Display, S: fmt::Display>(content: T, style: S) -> Styled<T, S> {
+    Styled::new(content, style)
+}
+
+/// Adds the `styled` method to every displayable type.
+pub trait Stylize: fmt::Display + Sized {
+    /// Wrap this value in `style`, so that it displays as the style, then the value, then `RESET`.
+    fn styled<S: fmt::Display>(self, style: S) -> Styled<Self, S> {
+        Styled::new(self, style)
+    }
+}
+
+impl<T: fmt::Display> Stylize for T {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::*;
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn style_display() {
+        assert_eq!(Style::new().to_string(), "");
+        assert_eq!(Style::new().bold().to_string(), BOLD);
+        assert_eq!(Style::new().fg(Color::Named(NamedColor::Green)).to_string(), GREEN);
+        assert_eq!(Style::new().bg(Color::Named(NamedColor::BrightBlue)).to_string(), BRIGHT_BLUE_BG);
+        assert_eq!(Style::new().fg(Color::Palette(214)).to_string(), color_256(214));
+        assert_eq!(Style::new().bg(Color::Rgb(1, 2, 3)).to_string(), truecolor_bg(1, 2, 3));
+        assert_eq!(
+            Style::new().italic().underline().fg(Color::Rgb(127, 45, 68)).bg(Color::Palette(7)).to_string(),
+            "\x1b[3;4;38;2;127;45;68;48;5;7m",
+        );
+    }
+
+    #[test]
+    fn style_flags() {
+        let style = Style::new().bold().add_flags(StyleFlags::ITALIC | StyleFlags::HIDDEN);
+        assert_eq!(style, Style::new().bold().italic().hidden());
+        assert_eq!(style.flags(), StyleFlags::BOLD | StyleFlags::ITALIC | StyleFlags::HIDDEN);
+        assert_eq!(style.to_string(), style.flags().enable().to_string());
+    }
+
+    #[test]
+    fn empty_styles() {
+        assert!(Style::default().is_empty());
+        assert_eq!(Style::default().to_string(), "");
+        assert!(Style::new().bold().when(false).is_empty());
+        assert!(!Style::new().strikethrough().is_empty());
+        assert!(!Style::new().fg(Color::Palette(0)).is_empty());
+        assert!(!Style::new().bg(Color::Palette(0)).is_empty());
+    }
+
+    #[test]
+    fn adding_styles() {
+        let (red, green, blue) = (Color::Named(NamedColor::Red), Color::Palette(2), Color::Rgb(0, 0, 255));
+
+        assert_eq!(Style::new().fg(red) + Style::new().fg(green),           Style::new().fg(green));
+        assert_eq!(Style::new().fg(red).bg(blue) + Style::new().bg(green),  Style::new().fg(red).bg(green));
+        assert_eq!(Style::new().bold() + Style::new().dim().italic(),       Style::new().bold().dim().italic());
+        assert_eq!(Style::new().bold().fg(red) + Style::new(),              Style::new().bold().fg(red));
+        assert_eq!(Style::new() + Style::new().underline().bg(blue),        Style::new().underline().bg(blue));
+
+        let mut style = Style::new().italic().fg(red);
+        style += Style::new().strikethrough().fg(blue);
+        style += Style::new().bg(green);
+        assert_eq!(style, Style::new().italic().strikethrough().fg(blue).bg(green));
+    }
+
+    #[test]
+    fn display_matches_param_writer() {
+        let colors = [
+            None,
+            Some(Color::Named(NamedColor::Red)),
+            Some(Color::Named(NamedColor::BrightWhite)),
+            Some(Color::Palette(7)),
+            Some(Color::Palette(214)),
+            Some(Color::Rgb(0, 9, 10)),
+            Some(Color::Rgb(99, 100, 255)),
+        ];
+
+        for bits in 0..1 << FLAG_PARAMS.len() {
+            let mut flags = StyleFlags::EMPTY;
+            for (i, (flag, _, _)) in FLAG_PARAMS.iter().enumerate() {
+                if bits & (1 << i) != 0 {
+                    flags = flags.union(*flag);
+                }
+            }
+
+            for fg in colors {
+                for bg in colors {
+                    let style = Style { flags, fg, bg };
+                    let mut expected = String::new();
+                    let mut params = ParamWriter::new(&mut expected);
+                    style.write_params(&mut params).unwrap();
+                    params.finish().unwrap();
+                    assert_eq!(style.to_string(), expected);
+                }
+            }
+        }
+
+        let longest = Style::new()
+            .bold().dim().italic().underline().blink().inverted().hidden().strikethrough()
+            .framed().encircled().overline()
+            .fg(Color::Rgb(255, 255, 255))
+            .bg(Color::Rgb(255, 255, 255));
+        assert_eq!(longest.to_string(), "\x1b[1;2;3;4;5;7;8;9;51;52;53;38;2;255;255;255;48;2;255;255;255m");
+        assert_eq!(longest.to_string().len(), MAX_CODE_LEN);
+    }
+
+    #[test]
+    fn parsing_styles() {
+        let (red, green) = (Color::Named(NamedColor::Red), Color::Rgb(0, 255, 0));
+
+        assert_eq!(Style::parse(""),                        Ok(Style::new()));
+        assert_eq!(Style::parse("\x1b[m"),                  Ok(Style::new()));
+        assert_eq!(Style::parse("\x1b[1;31m"),              Ok(Style::new().bold().fg(red)));
+        assert_eq!(Style::parse("\x1b[1m\x1b[31m"),         Ok(Style::new().bold().fg(red)));
+        assert_eq!(Style::parse("\x1b[3m\x1b[0;32m"),       Ok(Style::new().fg(Color::Named(NamedColor::Green))));
+        assert_eq!(Style::parse("\x1b[1;2;22;20m"),         Ok(Style::new()));
+        assert_eq!(Style::parse("\x1b[5m\x1b[6m"),          Ok(Style::new().blink()));
+        assert_eq!(Style::parse("\x1b[6m"),                 Ok(Style::new()));
+        assert_eq!(Style::parse("x"),                       Err(ParseSgrError::NotSgr));
+        assert_eq!(Style::parse("\x1b[2J"),                 Err(ParseSgrError::NotSgr));
+        assert_eq!(Style::parse("\x1b[1"),                  Err(ParseSgrError::NotSgr));
+        assert_eq!(Style::parse("\x1b[1:3m"),               Err(ParseSgrError::InvalidCharacter(':')));
+        assert_eq!("\x1b[7;48;2;0;255;0m".parse(),          Ok(Style::new().inverted().bg(green)));
+
+        for style in [
+            Style::new(),
+            Style::new().bold().dim().underline(),
+            Style::new().italic().fg(red).bg(Color::Palette(214)),
+            Style::new().blink().hidden().strikethrough().fg(green),
+        ] {
+            assert_eq!(Style::parse(&style.to_string()), Ok(style));
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn css_declarations() {
+        assert_eq!(Style::new().to_css(),                                   "");
+        assert_eq!(Style::new().blink().strikethrough().to_css(),           "");
+        assert_eq!(Style::new().italic().underline().to_css(),              "font-style: italic; text-decoration: underline");
+        assert_eq!(Style::new().fg(Color::Named(NamedColor::Red)).to_css(), "color: rgb(205, 0, 0)");
+        assert_eq!(Style::new().bg(Color::Palette(196)).to_css(),           "background-color: rgb(255, 0, 0)");
+        assert_eq!(Style::new().dim().fg(Color::Rgb(1, 2, 3)).to_css(),     "color: rgb(1, 2, 3)");
+    }
+
+    #[test]
+    fn styled_free_function() {
+        let bold = Style::new().bold();
+        assert_eq!(styled("ok", GREEN),                     "ok".styled(GREEN));
+        assert_eq!(format!("[{}]", styled("ok", GREEN)),    format!("[{GREEN}ok{RESET}]"));
+        assert_eq!(format!("{}", styled(42, bold)),         format!("{BOLD}42{RESET}"));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn apply_matches_concatenation() {
+        let styles = [
+            Style::new().bold(),
+            Style::new().dim().italic().underline(),
+            Style::new().strikethrough().fg(Color::Rgb(1, 2, 3)).bg(Color::Palette(4)),
+        ];
+        for style in styles {
+            assert_eq!(style.apply("text"), format!("{style}text{RESET}"));
+            assert_eq!(style.apply("text").matches('\x1b').count(), 2);
+        }
+        assert_eq!(Style::new().bold().apply(""), format!("{BOLD}{RESET}"));
+    }
+
+    #[test]
+    fn equal_styles_share_a_hash() {
+        use std::collections::HashMap;
+
+        let red = Color::Named(NamedColor::Red);
+        let mut cache = HashMap::new();
+        cache.insert(Style::new().bold().fg(red), Style::new().bold().fg(red).to_string());
+
+        let same = Style::new().fg(Color::Palette(3)).add_flags(StyleFlags::BOLD).fg(red);
+        assert_eq!(cache.get(&same), Some(&"\x1b[1;31m".to_string()));
+        assert_eq!(cache.get(&Style::new().bold().fg(Color::Palette(1))), None);
+        assert_eq!(Style::new().bold().italic(), Style::new().italic().bold().bold());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn diff_changes_only_what_differs() {
+        let red     = Color::Named(NamedColor::Red);
+        let green   = Color::Named(NamedColor::Green);
+        let base    = Style::new().bold().italic().fg(red).bg(Color::Palette(7));
+
+        assert_eq!(base.diff(&base), "");
+        assert_eq!(base.diff(&base.fg(green)), GREEN);
+        assert_eq!(base.diff(&base.underline()), UNDERLINE);
+        assert_eq!(base.diff(&base.dim()), DIM);
+        assert_eq!(base.bg(Color::Rgb(1, 2, 3)).diff(&base), color_256_bg(7));
+        assert_eq!(Style::new().dim().italic().diff(&Style::new().italic()), NORMAL_INTENSITY);
+        assert_eq!(Style::new().bold().dim().italic().diff(&Style::new().dim().italic()), "\x1b[22;2m");
+        assert_eq!(Style::new().italic().fg(red).diff(&Style::new().italic()), DEFAULT);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn intensity_stacking() {
+        let dim = Style::new().dim().fg(Color::Named(NamedColor::Red));
+        let both = dim.bold();
+        let bold = both.normal_intensity().bold();
+        let normal = bold.normal_intensity();
+
+        assert_eq!(both.flags(),                    StyleFlags::BOLD | StyleFlags::DIM);
+        assert_eq!(normal,                          Style::new().fg(Color::Named(NamedColor::Red)));
+        assert_eq!(dim.diff(&both),                 BOLD);
+        assert_eq!(both.diff(&bold),                "\x1b[22;1m");
+        assert_eq!(both.diff(&dim),                 "\x1b[22;2m");
+        assert_eq!(bold.diff(&normal),              NORMAL_INTENSITY);
+        assert_eq!(both.diff(&normal),              NORMAL_INTENSITY);
+        assert_eq!(normal.diff(&dim),               DIM);
+
+        let mut state = dim;
+        for param in parse_sgr("\x1b[2;22;1m").unwrap() {
+            state.apply_param(param);
+        }
+        assert_eq!(state, dim.normal_intensity().bold());
+    }
+
+    #[test]
+    fn inverting_styles() {
+        let (red, blue) = (Color::Named(NamedColor::Red), Color::Rgb(0, 0, 255));
+
+        assert_eq!(Style::new().invert(),                           Style::new());
+        assert_eq!(Style::new().bold().fg(red).invert(),            Style::new().bold().bg(red));
+        assert_eq!(red.on(blue).invert(),                           blue.on(red));
+        assert_eq!(red.on(blue).invert().invert(),                  red.on(blue));
+        assert_eq!(Style::new().inverted().bg(blue).invert(),       Style::new().inverted().fg(blue));
+        assert_eq!(Style::new().fg(red).invert().to_string(),       RED_BG);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn frame_and_overline() {
+        let rgb = Color::Rgb(1, 2, 3);
+        let italic = Style::new().italic();
+        let framed = italic.framed();
+        let both = framed.encircled();
+        let encircled = italic.encircled();
+
+        assert_eq!(both.to_string(),                "\x1b[3;51;52m");
+        assert_eq!(framed.diff(&both),              ENCIRCLED);
+        assert_eq!(both.diff(&encircled),           "\x1b[54;52m");
+        assert_eq!(both.diff(&framed),              "\x1b[54;51m");
+        assert_eq!(framed.diff(&italic),            NEITHER_FRAMED_NOR_ENCIRCLED);
+        assert_eq!(both.overline().diff(&both),     NOT_OVERLINED);
+        assert_eq!(both.diff(&both.overline()),     OVERLINE);
+        assert_eq!(reset_for(&both.overline()),     "\x1b[54;23;55m");
+        assert_eq!(Style::new().bold().framed().fg(rgb).diff(&Style::new().dim().encircled().fg(rgb)), "\x1b[22;54;2;52m");
+
+        let mut state = both.overline();
+        for param in parse_sgr("\x1b[54;55m").unwrap() {
+            state.apply_param(param);
+        }
+        assert_eq!(state, Style::new().italic());
+        assert_eq!(Style::parse("\x1b[51;52;53m"), Ok(Style::new().framed().encircled().overline()));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn reset_for_undoes_only_the_style() {
+        let styles = [
+            Style::new(),
+            Style::new().bold(),
+            Style::new().dim().italic(),
+            Style::new().bold().dim().strikethrough().fg(Color::Rgb(1, 2, 3)),
+            Style::new().inverted().hidden().bg(Color::Palette(9)),
+            Style::new().framed().overline(),
+            Style::new().framed().encircled().dim(),
+            Style::new().underline().blink().fg(Color::Named(NamedColor::Red)).bg(Color::Named(NamedColor::Blue)),
+        ];
+
+        for style in styles {
+            let mut state = style;
+            for param in parse_sgr(&reset_for(&style)).unwrap_or_default() {
+                assert_ne!(param, SgrParam::Reset);
+                state.apply_param(param);
+            }
+            assert_eq!(state, Style::new());
+        }
+
+        assert_eq!(reset_for(&Style::new()), "");
+        assert_eq!(reset_for(&Style::new().bold().dim()), NORMAL_INTENSITY);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn diff_prefers_reset_when_shorter() {
+        let busy = Style::new().bold().italic().underline().fg(Color::Palette(1)).bg(Color::Palette(2));
+        assert_eq!(busy.diff(&Style::new()), RESET);
+        assert_eq!(busy.diff(&Style::new().dim()), "\x1b[0;2m");
+        assert_eq!(Style::new().diff(&busy), busy.to_string());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn diff_reaches_target() {
+        let styles = [
+            Style::new(),
+            Style::new().bold(),
+            Style::new().dim().hidden(),
+            Style::new().bold().dim().fg(Color::Rgb(9, 8, 7)),
+            Style::new().italic().underline().blink().inverted().strikethrough(),
+            Style::new().bg(Color::Named(NamedColor::BrightCyan)).fg(Color::Palette(200)),
+        ];
+
+        for from in styles {
+            for to in styles {
+                let mut state = from;
+                let diff = from.diff(&to);
+                if !diff.is_empty() {
+                    for param in parse_sgr(&diff).unwrap() {
+                        state.apply_param(param);
+                    }
+                }
+                assert_eq!(state, to, "{from:?} -> {to:?} via {diff:?}");
+            }
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn conditional_styles() {
+        let style = Style::new().underline().bg(Color::Palette(3));
+        assert_eq!(style.when(true), style);
+        assert_eq!(style.when(false), Style::new());
+
+        assert_eq!(when(true, BOLD), BOLD);
+        assert_eq!(when(false, BOLD), "");
+        assert_eq!(when_owned(true, truecolor(1, 2, 3)), truecolor(1, 2, 3));
+        assert_eq!(when_owned(false, truecolor(1, 2, 3)), "");
+    }
+
+    #[test]
+    fn styled_resets() {
+        assert_eq!(Styled::new("ok", GREEN).to_string(), format!("{GREEN}ok{RESET}"));
+        assert_eq!("ok".styled(Style::new().bold().dim()).to_string(), "\x1b[1;2mok\x1b[0m");
+        assert_eq!(format!("[{}]", 42.styled(RED)), format!("[{RED}42{RESET}]"));
+        assert_eq!("ok".styled(Style::new()).to_string(), "ok");
+    }
+}
@@ -0,0 +1,1171 @@
+// Working with strings that already contain control codes.
+
+#[cfg(feature = "alloc")]
+use core::fmt;
+#[cfg(feature = "alloc")]
+use core::ops::Range;
+
+#[cfg(feature = "alloc")]
+use alloc::format;
+#[cfg(feature = "alloc")]
+use alloc::string::{String, ToString};
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use crate::scan::{tokens, Token};
+#[cfg(feature = "std")]
+use crate::scan::{escape_len_bytes, NoEscape};
+use crate::scan::sgr_params;
+use crate::sgr::parse_sgr_params;
+use crate::Style;
+#[cfg(feature = "alloc")]
+use crate::config::write_reset;
+#[cfg(feature = "alloc")]
+use crate::{Styled, RESET};
+
+/// Remove all control codes from a string, leaving only the text that would be displayed. This
+/// removes SGR codes (such as the constants of this crate) and other CSI sequences, OSC sequences
+/// (such as hyperlinks, whose link text is kept), and two-byte escapes. A malformed or incomplete
+/// control code is left in place.
+///
+/// ```
+/// use flower_pot::*;
+///
+/// assert_eq!(strip_ansi(&format!("{BOLD}{RED}error!{RESET}")), "error!");
+/// ```
+#[cfg(feature = "alloc")]
+pub fn strip_ansi(input: &str) -> String {
+    let mut out = String::new();
+    strip_ansi_into(input, &mut out);
+    out
+}
+
+/// Like `strip_ansi`, but appends the text to `out` instead of returning a new String, so that one
+/// buffer can be reused for many strings (such as the lines of a large log file).
+///
+/// ```
+/// use flower_pot::*;
+///
+/// let mut line = String::new();
+/// for input in [format!("{RED}first{RESET}"), format!("{BOLD}second{RESET}")] {
+///     line.clear();
+///     strip_ansi_into(&input, &mut line);
+///     println!("{line}");
+/// }
+/// ```
+#[cfg(feature = "alloc")]
+pub fn strip_ansi_into(input: &str, out: &mut String) {
+    for token in tokens(input) {
+        if let Token::Text(text) = token {
+            out.push_str(text);
+        }
+    }
+}
+
+/// A reader that removes control codes from the bytes read through it, the way `strip_ansi` does
+/// for strings, for cleaning up a styled stream (such as the output of a child process) without
+/// reading all of it first. It implements both `std::io::Read` and `std::io::BufRead`. Requires
+/// the `std` feature.
+///
+/// A control code that is split across reads from the underlying reader is held back until the
+/// rest of it arrives, so it is removed as if it had arrived all at once. A control code that is
+/// still incomplete when the underlying reader reaches the end of its input is passed through
+/// unchanged, like the incomplete control codes that `strip_ansi` leaves in place, as are
+/// malformed ones.
+///
+/// ```
+/// use std::io::Read;
+/// use flower_pot::*;
+///
+/// let styled = format!("{BOLD}{RED}error:{RESET} disk full\n");
+/// let mut plain = String::new();
+/// StripAnsiReader::new(styled.as_bytes()).read_to_string(&mut plain).unwrap();
+/// assert_eq!(plain, "error: disk full\n");
+/// ```
+#[cfg(feature = "std")]
+pub struct StripAnsiReader<R: std::io::Read> {
+    inner:  R,
+    raw:    Vec<u8>,
+    out:    Vec<u8>,
+    pos:    usize,
+    eof:    bool,
+}
+
+#[cfg(feature = "std")]
+impl<R: std::io::Read> StripAnsiReader<R> {
+    /// Wrap `inner`.
+    pub fn new(inner: R) -> StripAnsiReader<R> {
+        StripAnsiReader { inner, raw: Vec::new(), out: Vec::new(), pos: 0, eof: false }
+    }
+
+    /// Unwrap the underlying reader. Any bytes that have been read from it but not yet read
+    /// through this reader are lost.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// Move everything in `raw` that is known not to be part of an incomplete control code into
+    /// `out`, leaving out the control codes.
+    fn strip(&mut self) {
+        let raw = &self.raw;
+        let mut i = 0;
+
+        while i < raw.len() {
+            if raw[i] != 0x1b {
+                let end = raw[i..].iter().position(|&b| b == 0x1b).map_or(raw.len(), |n| i + n);
+                self.out.extend_from_slice(&raw[i..end]);
+                i = end;
+                continue;
+            }
+
+            match escape_len_bytes(&raw[i..]) {
+                Ok(len)                                 => i += len,
+                Err(NoEscape::Incomplete) if !self.eof  => break,
+                Err(NoEscape::Incomplete)               => {
+                    self.out.extend_from_slice(&raw[i..]);
+                    i = raw.len();
+                }
+                Err(NoEscape::Malformed)                => {
+                    self.out.push(0x1b);
+                    i += 1;
+                }
+            }
+        }
+
+        self.raw.drain(..i);
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R: std::io::Read> std::io::BufRead for StripAnsiReader<R> {
+    fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+        while self.pos == self.out.len() && !self.eof {
+            self.out.clear();
+            self.pos = 0;
+
+            let mut chunk = [0; 4096];
+            let n = match self.inner.read(&mut chunk) {
+                Ok(n)                                                       => n,
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted       => continue,
+                Err(e)                                                      => return Err(e),
+            };
+
+            self.eof = n == 0;
+            self.raw.extend_from_slice(&chunk[..n]);
+            self.strip();
+        }
+
+        Ok(&self.out[self.pos..])
+    }
+
+    fn consume(&mut self, amount: usize) {
+        self.pos = (self.pos + amount).min(self.out.len());
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R: std::io::Read> std::io::Read for StripAnsiReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        use std::io::BufRead;
+
+        let available = self.fill_buf()?;
+        let n = available.len().min(buf.len());
+        buf[..n].copy_from_slice(&available[..n]);
+        self.consume(n);
+        Ok(n)
+    }
+}
+
+/// What `sanitize_with` does with control characters.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Sanitize {
+    /// Remove them.
+    Drop,
+    /// Replace them with a visible caret notation, the way `cat -v` shows them: `^[` for the escape
+    /// character, `^A` through `^_` for the other C0 controls, `^?` for DEL, and `M-` followed by
+    /// the notation for the corresponding C0 control for the C1 controls (so the single-character
+    /// CSI, U+009B, becomes `M-^[`).
+    Escape,
+}
+
+/// Remove control characters from untrusted text (such as user input, file names, or the output
+/// of another program) before printing it, so that it can't inject control codes of its own. Text
+/// containing `\x1b[` could otherwise change the styling of everything after it, move the cursor,
+/// rewrite the window title, or worse. This removes the C0 controls (including the escape
+/// character `\x1b`), DEL, and the C1 controls (U+0080 to U+009F, some of which terminals treat
+/// like escape sequences), but keeps newlines and tabs. Use `sanitize_with` to make the removed
+/// characters visible instead.
+///
+/// Sanitize each untrusted piece on its own, before adding your own styling around it:
+///
+/// ```
+/// use flower_pot::*;
+///
+/// let name = "evil\x1b[8m";
+/// let line = format!("{BOLD}user:{RESET} {}", sanitize(name));
+/// assert_eq!(line, format!("{BOLD}user:{RESET} evil[8m"));
+/// ```
+#[cfg(feature = "alloc")]
+pub fn sanitize(input: &str) -> String {
+    sanitize_with(input, Sanitize::Drop)
+}
+
+/// Like `sanitize`, but lets you choose whether control characters are removed or shown in caret
+/// notation (see `Sanitize`), which is useful for seeing what an untrusted string contained.
+///
+/// ```
+/// use flower_pot::*;
+///
+/// assert_eq!(sanitize_with("evil\x1b[8m\x07", Sanitize::Escape), "evil^[[8m^G");
+/// ```
+#[cfg(feature = "alloc")]
+pub fn sanitize_with(input: &str, mode: Sanitize) -> String {
+    let mut out = String::with_capacity(input.len());
+    for c in input.chars() {
+        if c == '\n' || c == '\t' || !c.is_control() {
+            out.push(c);
+        } else if mode == Sanitize::Escape {
+            let mut code = c as u32;
+            if code >= 0x80 {
+                out.push_str("M-");
+                code -= 0x80;
+            }
+            out.push('^');
+            out.push(if code == 0x7f { '?' } else { char::from(code as u8 + b'@') });
+        }
+    }
+    out
+}
+
+/// The number of columns a string takes up when displayed, not counting its control codes (the
+/// ones `strip_ansi` removes). Each character counts as one column, so this is only exact for text
+/// without wide characters (such as most CJK characters and emoji) or combining characters.
+///
+/// ```
+/// use flower_pot::*;
+///
+/// assert_eq!(visible_width(&format!("{GREEN}ok{RESET}")), 2);
+/// ```
+pub fn visible_width(input: &str) -> usize {
+    tokens(input)
+        .map(|token| match token {
+            Token::Text(text)   => text.chars().count(),
+            Token::Escape(_)    => 0,
+        })
+        .sum()
+}
+
+/// The number of bytes of a string taken up by control codes (the ones `strip_ansi` removes), for
+/// estimating how much overhead styling adds. For any string `s`, `ansi_len(s)` plus
+/// `strip_ansi(s).len()` is `s.len()`.
+///
+/// ```
+/// use flower_pot::*;
+///
+/// assert_eq!(ansi_len(&format!("{GREEN}ok{RESET}")), GREEN.len() + RESET.len());
+/// ```
+pub fn ansi_len(input: &str) -> usize {
+    tokens(input)
+        .map(|token| match token {
+            Token::Text(_)          => 0,
+            Token::Escape(escape)   => escape.len(),
+        })
+        .sum()
+}
+
+/// The fraction of a string's bytes that are taken up by control codes (see `ansi_len`), from 0.0
+/// for plain text to 1.0 for a string of nothing but control codes, as a quick measure of how
+/// chatty some styled output is. The empty string gives 0.0.
+///
+/// ```
+/// use flower_pot::*;
+///
+/// assert_eq!(overhead_ratio("plain"), 0.0);
+/// assert_eq!(overhead_ratio(&format!("{BOLD}abcdefgh{RESET}")), 0.5); // 8 bytes out of 16
+/// ```
+pub fn overhead_ratio(input: &str) -> f32 {
+    if input.is_empty() {
+        return 0.0;
+    }
+
+    ansi_len(input) as f32 / input.len() as f32
+}
+
+/// Panic if the fraction of the bytes of `input` taken up by control codes (see `overhead_ratio`)
+/// is more than `threshold`, as a development-time check on output that is styled far more
+/// heavily than it needs to be: for example, a code for every character where the style only
+/// changes a few times, which is a sign that the output should be built with `Style::diff` (or
+/// `map_chars`, which uses it) instead. Like `debug_assert!`, this only checks anything in builds
+/// with debug assertions enabled, and does nothing in release builds.
+///
+/// ```
+/// use flower_pot::*;
+///
+/// assert_reasonable_overhead(&format!("{RED}error:{RESET} disk full"), 0.5);
+///
+/// // With debug assertions, this would panic, since control codes take up 24 of its 28 bytes:
+/// // assert_reasonable_overhead(&format!("{RED}o{RED}o{RED}p{RED}s{RESET}"), 0.5);
+/// ```
+#[track_caller]
+pub fn assert_reasonable_overhead(input: &str, threshold: f32) {
+    if cfg!(debug_assertions) {
+        let ratio = overhead_ratio(input);
+        if ratio > threshold {
+            panic!(
+                "control codes take up {:.0}% of the output, more than the threshold of {:.0}%",
+                ratio * 100.0,
+                threshold * 100.0,
+            );
+        }
+    }
+}
+
+/// The byte ranges of the control codes in a string (the ones `strip_ansi` removes), in order, for
+/// editors and highlighters that need to treat the control codes specially. Adjacent control
+/// codes get separate ranges. An incomplete or malformed control code (such as one cut off at the
+/// end of the string) isn't included, just as `strip_ansi` keeps it as text, so removing all of
+/// the ranges from `input` gives `strip_ansi(input)`.
+///
+/// ```
+/// use flower_pot::*;
+///
+/// let text = format!("{BOLD}{RED}error{RESET}: \x1b[3");
+/// assert_eq!(ansi_find(&text), [0..4, 4..9, 14..18]);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn ansi_find(input: &str) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    let mut start = 0;
+    for token in tokens(input) {
+        let end = match token {
+            Token::Text(text)       => start + text.len(),
+            Token::Escape(escape)   => {
+                ranges.push(start..start + escape.len());
+                start + escape.len()
+            }
+        };
+        start = end;
+    }
+    ranges
+}
+
+/// The size of a block of text containing control codes, as `(rows, cols)`: the number of lines,
+/// and the `visible_width` of the widest one. Lines are split like `str::lines`, so a trailing
+/// newline doesn't start another row, and an empty string takes up no rows.
+///
+/// ```
+/// use flower_pot::*;
+///
+/// let block = format!("{BOLD}title{RESET}\n{RED}a longer line{RESET}\n");
+/// assert_eq!(block_size(&block), (2, 13));
+/// ```
+pub fn block_size(input: &str) -> (usize, usize) {
+    input.lines().fold((0, 0), |(rows, cols), line| (rows + 1, cols.max(visible_width(line))))
+}
+
+/// Shorten a string containing control codes so that it takes up at most `max_cols` columns (as
+/// measured by `visible_width`), keeping its styling. The string is cut between characters, never
+/// in the middle of a control code, and control codes after the cut are dropped. If any style is
+/// still active at the cut (that is, if the kept part set a style without resetting it
+/// afterwards), `RESET` is added so that it doesn't leak into whatever comes next. A string that
+/// already fits is returned unchanged.
+///
+/// ```
+/// use flower_pot::*;
+///
+/// let text = format!("{RED}abcdef{RESET}");
+/// assert_eq!(truncate_visible(&text, 3), format!("{RED}abc{RESET}"));
+/// ```
+#[cfg(feature = "alloc")]
+pub fn truncate_visible(input: &str, max_cols: usize) -> String {
+    truncate_visible_with(input, max_cols, "")
+}
+
+/// Like `truncate_visible`, but if the string has to be cut, ends it with `ellipsis` (such as
+/// `"…"` or `"..."`), which should be plain text. The ellipsis counts towards `max_cols` and is
+/// displayed in the style that is active at the cut. If the ellipsis itself is wider than
+/// `max_cols`, only as much of it as fits is used.
+///
+/// ```
+/// use flower_pot::*;
+///
+/// let text = format!("{BOLD}long filename.txt{RESET}");
+/// assert_eq!(truncate_visible_with(&text, 6, "…"), format!("{BOLD}long …{RESET}"));
+/// ```
+#[cfg(feature = "alloc")]
+pub fn truncate_visible_with(input: &str, max_cols: usize, ellipsis: &str) -> String {
+    if visible_width(input) <= max_cols {
+        return input.to_string();
+    }
+
+    let ellipsis_cols = ellipsis.chars().count();
+    let mut remaining = max_cols.saturating_sub(ellipsis_cols);
+    let mut out = String::new();
+    let mut style = Style::new();
+
+    for token in tokens(input) {
+        if remaining == 0 {
+            break;
+        }
+
+        match token {
+            Token::Escape(escape) => {
+                out.push_str(escape);
+                style = apply(style, escape);
+            }
+            Token::Text(text) => {
+                let end = text.char_indices().nth(remaining).map_or(text.len(), |(i, _)| i);
+                out.push_str(&text[..end]);
+                remaining -= text[..end].chars().count();
+            }
+        }
+    }
+
+    out.extend(ellipsis.chars().take(max_cols));
+    if !style.is_empty() {
+        out.push_str(RESET);
+    }
+    out
+}
+
+/// Word-wrap a string containing SGR control codes into lines of at most `width` columns (as
+/// measured by `visible_width`), for fitting styled paragraphs into a fixed-width box. Lines are
+/// broken at whitespace, and the whitespace at each break is dropped; a word that is wider than
+/// `width` on its own is broken wherever it reaches the width. Newlines in the text always start a
+/// new line. A `width` of 0 is treated as 1.
+///
+/// Each line is self-contained: it starts with a control code for the style that is active at its
+/// first character (so styling carries on across a break), and ends with `RESET` if any style is
+/// still active at its end. Within a line, a control code is only added where the style changes.
+/// Styles are tracked the same way as for `spans`, so control codes other than SGR codes, and SGR
+/// parameters that a `Style` can't represent, are left out of the result.
+///
+/// ```
+/// use flower_pot::*;
+///
+/// let text = format!("the {RED}quick brown{RESET} fox");
+/// assert_eq!(wrap_visible(&text, 9), [
+///     format!("the {RED}quick{RESET}"),
+///     format!("{RED}brown{RESET} fox"),
+/// ]);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn wrap_visible(text: &str, width: usize) -> Vec<String> {
+    let width = width.max(1);
+
+    let mut chars = Vec::new();
+    for span in spans(text) {
+        chars.extend(span.text.chars().map(|c| (c, span.style)));
+    }
+
+    let render = |line: &[(char, Style)]| {
+        let mut out = String::new();
+        let mut current = Style::new();
+        for &(c, style) in line {
+            out.push_str(&current.diff(&style));
+            out.push(c);
+            current = style;
+        }
+        if !current.is_empty() {
+            out.push_str(RESET);
+        }
+        out
+    };
+
+    let mut lines = Vec::new();
+    if text.is_empty() {
+        return lines;
+    }
+
+    for paragraph in chars.split(|&(c, _)| c == '\n') {
+        let mut line: Vec<(char, Style)> = Vec::new();
+        let mut rest = paragraph;
+
+        // Keep the paragraph's indentation, but not the whitespace at later breaks.
+        let indent = rest.iter().take_while(|(c, _)| c.is_whitespace()).count();
+        let mut gap = &rest[..indent];
+        rest = &rest[indent..];
+
+        while !rest.is_empty() {
+            let len = rest.iter().take_while(|(c, _)| !c.is_whitespace()).count();
+            let mut word = &rest[..len];
+            rest = &rest[len..];
+
+            if !line.is_empty() || !gap.is_empty() {
+                if line.len() + gap.len() + word.len() <= width {
+                    line.extend_from_slice(gap);
+                } else if !line.is_empty() {
+                    lines.push(render(&line));
+                    line.clear();
+                }
+            }
+
+            while line.len() + word.len() > width {
+                let (head, tail) = word.split_at(width - line.len());
+                line.extend_from_slice(head);
+                lines.push(render(&line));
+                line.clear();
+                word = tail;
+            }
+            line.extend_from_slice(word);
+
+            let len = rest.iter().take_while(|(c, _)| c.is_whitespace()).count();
+            gap = &rest[..len];
+            rest = &rest[len..];
+        }
+
+        lines.push(render(&line));
+    }
+
+    lines
+}
+
+/// Highlight every occurrence of `needle` in a string containing control codes, for showing search
+/// matches in styled text. Occurrences are found in the visible text (as `strip_ansi` would give
+/// it), so a match can span control codes, and control codes are never split. Each match is
+/// displayed in the style around it with `style` added on top (as `+` adds styles), and the style
+/// around it is restored right after it. Only what differs is changed, and there are no resets,
+/// so styling the input set outside the match (including any that a `Style` can't represent) is
+/// kept as it is. The control codes already in the input are all kept; if one of them changes the
+/// style inside a match, `style` is added again after it.
+///
+/// Matches are found from left to right and don't overlap: in `"aaa"`, the needle `"aa"` only
+/// matches the first two characters. An empty needle matches nothing, so the input is returned
+/// unchanged.
+///
+/// ```
+/// use flower_pot::*;
+///
+/// let found = Style::new().bg(Color::Named(NamedColor::Yellow));
+/// let line = format!("{RED}error: {BOLD}file{RESET} not found");
+///
+/// assert_eq!(highlight(&line, "or: f", &found), format!(
+///     "{RED}err{YELLOW_BG}or: {BOLD}f{DEFAULT_BG}ile{RESET} not found",
+/// ));
+/// ```
+#[cfg(feature = "alloc")]
+pub fn highlight(input: &str, needle: &str, style: &Style) -> String {
+    if needle.is_empty() {
+        return input.to_string();
+    }
+
+    let visible = strip_ansi(input);
+    let mut matches = visible.match_indices(needle).map(|(start, _)| start..start + needle.len()).peekable();
+
+    // `around` is the style that the input sets at the current position, and `terminal` is the
+    // style the output so far leaves the terminal in, which has `style` added inside a match.
+    let mut out = String::with_capacity(input.len());
+    let (mut around, mut terminal) = (Style::new(), Style::new());
+    let mut in_match = false;
+    let mut pos = 0;
+
+    let change = |terminal: &mut Style, to: Style, out: &mut String| {
+        let _ = terminal.write_change(&to, false, &[], out);
+        *terminal = to;
+    };
+
+    for token in tokens(input) {
+        match token {
+            Token::Escape(escape) => {
+                out.push_str(escape);
+                around = apply(around, escape);
+                terminal = apply(terminal, escape);
+                if in_match {
+                    change(&mut terminal, around + *style, &mut out);
+                }
+            }
+            Token::Text(mut text) => {
+                while !text.is_empty() {
+                    if !in_match && matches.peek().is_some_and(|m| m.start == pos) {
+                        change(&mut terminal, around + *style, &mut out);
+                        in_match = true;
+                    }
+
+                    let boundary = matches.peek().map_or(usize::MAX, |m| if in_match { m.end } else { m.start });
+                    let (before, after) = text.split_at((boundary - pos).min(text.len()));
+                    out.push_str(before);
+                    pos += before.len();
+                    text = after;
+
+                    if in_match && pos == boundary {
+                        change(&mut terminal, around, &mut out);
+                        in_match = false;
+                        matches.next();
+                    }
+                }
+            }
+        }
+    }
+
+    out
+}
+
+/// Where to put text within a wider column. Used by `pad_visible`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Align {
+    /// Put the text at the start of the column, with the padding after it.
+    Left,
+    /// Put the text at the end of the column, with the padding before it.
+    Right,
+    /// Put the text in the middle of the column. If the padding can't be split evenly, the extra
+    /// space goes after the text.
+    Center,
+}
+
+/// Pad a string containing control codes with spaces so that it takes up `width` columns (as
+/// measured by `visible_width`, so the control codes don't count), for lining up styled text in
+/// tables. The spaces are added before and after the whole string, outside of its styling, so
+/// they aren't colored as long as the string ends with a `RESET` (as strings from `styled` do).
+/// A string that is already at least `width` columns wide is returned unchanged.
+///
+/// ```
+/// use flower_pot::*;
+///
+/// let cell = "ok".styled(GREEN).to_string();
+/// assert_eq!(pad_visible(&cell, 6, Align::Right), format!("    {GREEN}ok{RESET}"));
+/// assert_eq!(pad_visible(&cell, 5, Align::Center), format!(" {GREEN}ok{RESET}  "));
+/// ```
+#[cfg(feature = "alloc")]
+pub fn pad_visible(input: &str, width: usize, align: Align) -> String {
+    let padding = width.saturating_sub(visible_width(input));
+    let (before, after) = match align {
+        Align::Left     => (0, padding),
+        Align::Right    => (padding, 0),
+        Align::Center   => (padding / 2, padding - padding / 2),
+    };
+
+    let mut out = String::with_capacity(input.len() + padding);
+    out.extend(core::iter::repeat_n(' ', before));
+    out.push_str(input);
+    out.extend(core::iter::repeat_n(' ', after));
+    out
+}
+
+/// Draw a box around a block of text containing control codes, with the border (`┌─┐│└┘`) styled
+/// with `style`. Lines are split like `str::lines` and padded with spaces to the width of the
+/// widest one (as measured by `visible_width`, so the control codes don't count). Any styling in
+/// the text is kept, and a line that leaves a style active gets a `RESET` before its padding, so
+/// its styling doesn't leak into the padding or the border. Each piece of the border is styled the
+/// same way `styled` would style it, so while styling is turned off (see `set_colors_enabled`),
+/// only the characters are drawn, and the `RESET`s are written in the form chosen with
+/// `set_csi_form`. The box doesn't end with a newline.
+///
+/// ```
+/// use flower_pot::*;
+///
+/// let text = format!("{RED}error{RESET}\nfile not found");
+/// println!("{}", boxed(&text, &Style::new().dim()));
+///
+/// assert_eq!(strip_ansi(&boxed(&text, &Style::new())), concat!(
+///     "┌──────────────┐\n",
+///     "│error         │\n",
+///     "│file not found│\n",
+///     "└──────────────┘",
+/// ));
+/// ```
+#[cfg(feature = "alloc")]
+pub fn boxed(text: &str, style: &Style) -> String {
+    let (_, width) = block_size(text);
+    let horizontal = "─".repeat(width);
+    let border = |s: &str| Styled::new(s, style).to_string();
+
+    let mut out = border(&format!("┌{horizontal}┐"));
+    let side = border("│");
+    for line in text.lines() {
+        out.push('\n');
+        out.push_str(&side);
+        out.push_str(line);
+        if !apply(Style::new(), line).is_empty() {
+            let _ = write_reset(&mut out);
+        }
+        out.extend(core::iter::repeat_n(' ', width - visible_width(line)));
+        out.push_str(&side);
+    }
+    out.push('\n');
+    out.push_str(&border(&format!("└{horizontal}┘")));
+    out
+}
+
+/// A string being built out of styled and plain pieces, which keeps track of its visible width
+/// (see `visible_width`) as it goes, so that the width doesn't have to be measured again at the
+/// end. Only the pieces themselves are scanned for control codes, once each, as they are added.
+///
+/// ```
+/// use flower_pot::*;
+///
+/// let mut line = AnsiString::new();
+/// line.push_styled(BOLD, "name");
+/// line.push_plain(": ");
+/// line.push_styled(Style::new().fg(Color::Named(NamedColor::Green)), "ok");
+///
+/// assert_eq!(line.width(), 8);
+/// let (text, width) = line.into_parts();
+/// assert_eq!(text, format!("{BOLD}name{RESET}: \x1b[32mok{RESET}"));
+/// assert_eq!(width, 8);
+/// ```
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AnsiString {
+    text:   String,
+    width:  usize,
+}
+
+#[cfg(feature = "alloc")]
+impl AnsiString {
+    /// Create an empty string.
+    pub const fn new() -> AnsiString {
+        AnsiString { text: String::new(), width: 0 }
+    }
+
+    /// Add `text` wrapped in `style`, followed by `RESET`, the same way `styled` would (so only the
+    /// text is added while styling is turned off; see `set_colors_enabled`).
+    pub fn push_styled(&mut self, style: impl fmt::Display, text: &str) {
+        use core::fmt::Write;
+
+        let _ = write!(self.text, "{}", Styled::new(text, style));
+        self.width += visible_width(text);
+    }
+
+    /// Add `text` without any styling. If `text` contains control codes of its own, they are added
+    /// too, but don't count towards the width.
+    pub fn push_plain(&mut self, text: &str) {
+        self.text.push_str(text);
+        self.width += visible_width(text);
+    }
+
+    /// The visible width of the string so far (see `visible_width`).
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// The string so far, including its control codes.
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    /// The finished string and its visible width.
+    pub fn into_parts(self) -> (String, usize) {
+        (self.text, self.width)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl fmt::Display for AnsiString {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+/// The style that the terminal would be in after receiving the control codes in `seq`, starting
+/// from `base`: `RESET` clears the style, `NOT_UNDERLINED` turns off underlining, a new foreground
+/// color replaces the old one, and so on. Parameters that a `Style` can't represent are ignored, as
+/// are control codes other than SGR codes, malformed SGR codes, and any text in `seq`.
+///
+/// ```
+/// use flower_pot::*;
+///
+/// let base = Style::new().bold();
+/// let red = Color::Named(NamedColor::Red);
+///
+/// assert_eq!(apply(base, RED), base.fg(red));
+/// assert_eq!(apply(base, &format!("{ITALIC}{RESET}{RED}")), Style::new().fg(red));
+/// ```
+pub fn apply(base: Style, seq: &str) -> Style {
+    let mut style = base;
+    for token in tokens(seq) {
+        if let Token::Escape(escape) = token && let Some(Ok(params)) = sgr_params(escape).map(parse_sgr_params) {
+            for param in params {
+                style.apply_param(param);
+            }
+        }
+    }
+    style
+}
+
+/// A run of plain text and the style it is displayed in. Returned by `spans`.
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Span {
+    /// The text, with no control codes in it.
+    pub text: String,
+    /// The style that is active for the whole of `text`.
+    pub style: Style,
+}
+
+/// Split a string containing SGR control codes into runs of plain text, each paired with the
+/// style that is active for it. The style starts out empty and accumulates the effect of each
+/// control code the way a terminal would: `RESET` clears it, `NOT_UNDERLINED` turns off
+/// underlining, a new foreground color replaces the old one, and so on. Parameters that a `Style`
+/// can't represent are ignored, as are control codes other than SGR codes (such as hyperlinks).
+/// Adjacent runs of text with the same style are merged into one span, and no span is empty.
+///
+/// ```
+/// use flower_pot::*;
+///
+/// let spans = spans(&format!("plain {BOLD}bold {RED}red{RESET}"));
+/// assert_eq!(spans, [
+///     Span { text: "plain ".to_string(), style: Style::new() },
+///     Span { text: "bold ".to_string(), style: Style::new().bold() },
+///     Span { text: "red".to_string(), style: Style::new().bold().fg(Color::Named(NamedColor::Red)) },
+/// ]);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn spans(input: &str) -> Vec<Span> {
+    let mut spans: Vec<Span> = Vec::new();
+    let mut style = Style::new();
+
+    for token in tokens(input) {
+        match token {
+            Token::Escape(escape) => style = apply(style, escape),
+            Token::Text(text) => {
+                match spans.last_mut() {
+                    Some(last) if last.style == style => last.text.push_str(text),
+                    _ => spans.push(Span { text: text.to_string(), style }),
+                }
+            }
+        }
+    }
+
+    spans
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::*;
+
+    #[cfg(feature = "alloc")]
+    fn span(text: &str, style: Style) -> Span {
+        Span { text: text.to_string(), style }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn spans_accumulate() {
+        let red = Color::Named(NamedColor::Red);
+        let input = format!("{BOLD}a{}b{NOT_BOLD}{NORMAL_INTENSITY}c{RESET}d", color_256_bg(3));
+        assert_eq!(spans(&input), [
+            span("a", Style::new().bold()),
+            span("b", Style::new().bold().bg(Color::Palette(3))),
+            span("c", Style::new().bg(Color::Palette(3))),
+            span("d", Style::new()),
+        ]);
+        assert_eq!(spans(&format!("{RED}x{BOLD}{NOT_BOLD}{NORMAL_INTENSITY}y")), [span("xy", Style::new().fg(red))]);
+    }
+
+    #[test]
+    fn apply_tracks_state() {
+        let red = Color::Named(NamedColor::Red);
+        let base = Style::new().italic().bg(Color::Palette(4));
+
+        assert_eq!(apply(base, ""),                                                 base);
+        assert_eq!(apply(base, "\x1b[1;31;0;4m"),                                   Style::new().underline());
+        assert_eq!(apply(base, &format!("{BOLD}{RED}{RESET}{DIM}")),                Style::new().dim());
+        assert_eq!(apply(base, &format!("{NEITHER_BOLD_NOR_ITALIC}{DEFAULT_BG}")),  Style::new());
+        assert_eq!(apply(base, &format!("{RED}text{FRAKTUR}\x1b[4:3m\x1b[1")),      base.fg(red));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn spans_ignore_unknown() {
+        let input = format!("{ITALIC}a\x1b[56;4mb{}c\x1b[4:3md", hyperlink("url", "x"));
+        assert_eq!(spans(&input), [
+            span("a", Style::new().italic()),
+            span("bxcd", Style::new().italic().underline()),
+        ]);
+        assert_eq!(spans(""), []);
+        assert_eq!(spans(&format!("{BOLD}{RESET}")), []);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn strip_and_measure() {
+        let inputs = [
+            "",
+            "plain",
+            &format!("{BOLD}{RED}error!{RESET}"),
+            &format!("a{}b{RESET}é", truecolor(1, 2, 3)),
+            &hyperlink("https://example.com", "link"),
+            "\x1b7saved\x1b8",
+            "broken \x1b[2",
+            "a\x1b\x1b[31mb",
+        ];
+        let expected = ["", "plain", "error!", "abé", "link", "saved", "broken \x1b[2", "a\x1bb"];
+
+        for (input, expected) in inputs.into_iter().zip(expected) {
+            assert_eq!(strip_ansi(input), expected);
+            assert_eq!(visible_width(input), expected.chars().count());
+            assert_eq!(ansi_len(input) + expected.len(), input.len());
+        }
+    }
+
+    #[test]
+    fn overhead_ratios() {
+        assert_eq!(overhead_ratio(""),                                  0.0);
+        assert_eq!(overhead_ratio("plain"),                             0.0);
+        assert_eq!(overhead_ratio(RESET),                               1.0);
+        assert_eq!(overhead_ratio(&format!("{BOLD}abcdefgh{RESET}")),   0.5);
+        assert_eq!(overhead_ratio("broken \x1b[2"),                     0.0);
+
+        assert_reasonable_overhead("plain", 0.0);
+        assert_reasonable_overhead(&format!("{BOLD}abcdefgh{RESET}"), 0.5);
+        assert_reasonable_overhead(RESET, 1.0);
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    #[should_panic(expected = "control codes take up 50% of the output, more than the threshold of 25%")]
+    fn chatty_output_panics() {
+        assert_reasonable_overhead(&format!("{BOLD}abcdefgh{RESET}"), 0.25);
+    }
+
+    #[cfg(not(debug_assertions))]
+    #[test]
+    fn chatty_output_is_ignored_in_release_builds() {
+        assert_reasonable_overhead(&format!("{BOLD}abcdefgh{RESET}"), 0.25);
+        assert_reasonable_overhead(&format!("{BOLD}{RESET}"), 0.0);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn finding_control_codes() {
+        let link = hyperlink("url", "x");
+        let inputs = [
+            String::new(),
+            "plain".to_string(),
+            format!("{BOLD}{ITALIC}é{RESET}"),
+            format!("a{link}b\x1b7"),
+            "cut \x1b[38;5".to_string(),
+            "\x1b[1x\x1b[m\x1b".to_string(),
+        ];
+        let expected: [&[Range<usize>]; 6] = [
+            &[],
+            &[],
+            &[0..4, 4..8, 10..14],
+            &[1..11, 12..19, 20..22],
+            &[],
+            &[0..4, 4..7],
+        ];
+
+        for (input, expected) in inputs.iter().zip(expected) {
+            let ranges = ansi_find(input);
+            assert_eq!(ranges, expected, "{input:?}");
+
+            let mut kept = String::new();
+            let mut end = 0;
+            for range in ranges {
+                kept.push_str(&input[end..range.start]);
+                end = range.end;
+            }
+            kept.push_str(&input[end..]);
+            assert_eq!(kept, strip_ansi(input));
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn highlights() {
+        let found = Style::new().bold().bg(Color::Named(NamedColor::Yellow));
+        let hl = |input: &str, needle: &str| highlight(input, needle, &found);
+
+        assert_eq!(hl("a cat, a hat", "at"),         "a c\x1b[1;43mat\x1b[22;49m, a h\x1b[1;43mat\x1b[22;49m");
+        assert_eq!(hl("aaaa", "aa"),                 "\x1b[1;43maa\x1b[22;49m\x1b[1;43maa\x1b[22;49m");
+        assert_eq!(hl("aaa", "aa"),                  "\x1b[1;43maa\x1b[22;49ma");
+        assert_eq!(hl("abc", ""),                    "abc");
+        assert_eq!(hl("abc", "x"),                   "abc");
+        assert_eq!(hl("", "x"),                      "");
+        assert_eq!(hl("héllo wörld", "ö"),           "héllo w\x1b[1;43mö\x1b[22;49mrld");
+
+        // Control codes in the input are kept, and the highlight is restored after them.
+        let input = format!("{GREEN}ab{RESET}cd{OVERLINE}e");
+        assert_eq!(hl(&input, "bc"), format!("{GREEN}a\x1b[1;43mb{RESET}\x1b[1;43mc\x1b[22;49md{OVERLINE}e"));
+        assert_eq!(hl(&input, "e"),  format!("{GREEN}ab{RESET}cd{OVERLINE}\x1b[1;43me\x1b[22;49m"));
+
+        // The matched text is the same, whatever the input's styling.
+        let styled = format!("{BOLD}x{RESET}y{}z", color_256(3));
+        assert_eq!(strip_ansi(&hl(&styled, "xyz")), "xyz");
+        assert!(spans(&hl(&styled, "xyz")).iter().all(|span| span.style.bg == Some(Color::Named(NamedColor::Yellow))));
+    }
+
+    #[test]
+    fn block_sizes() {
+        assert_eq!(block_size(""),                                      (0, 0));
+        assert_eq!(block_size("\n"),                                    (1, 0));
+        assert_eq!(block_size("abc"),                                   (1, 3));
+        assert_eq!(block_size("abc\n"),                                 (1, 3));
+        assert_eq!(block_size("a\n\nbcd"),                              (3, 3));
+        assert_eq!(block_size("ab\r\ncd\r\n"),                          (2, 2));
+        assert_eq!(block_size(&format!("{RED}héllo{RESET}\n{BOLD}x")),  (2, 5));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn boxes() {
+        let dim = Style::new().dim();
+        let side = format!("{DIM}│{RESET}");
+        let text = format!("{RED}héllo{RESET}\n{BOLD}x\n");
+        assert_eq!(boxed(&text, &dim), [
+            format!("{DIM}┌─────┐{RESET}"),
+            format!("{side}{RED}héllo{RESET}{side}"),
+            format!("{side}{BOLD}x{RESET}    {side}"),
+            format!("{DIM}└─────┘{RESET}"),
+        ].join("\n"));
+
+        assert_eq!(strip_ansi(&boxed("", &dim)),        "┌┐\n└┘");
+        assert_eq!(strip_ansi(&boxed("ab\n\n", &dim)),  "┌──┐\n│ab│\n│  │\n└──┘");
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn strip_into_appends() {
+        let mut out = String::from("> ");
+        strip_ansi_into(&format!("{UNDERLINE}a{RESET}b"), &mut out);
+        strip_ansi_into("", &mut out);
+        strip_ansi_into(&hyperlink("url", "c"), &mut out);
+        assert_eq!(out, "> abc");
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn sanitize_controls() {
+        let input = "a\x1b[31mb\x00\x7f\u{9b}1mc\td\né";
+        assert_eq!(sanitize(input),                              "a[31mb1mc\td\né");
+        assert_eq!(sanitize_with(input, Sanitize::Drop),         "a[31mb1mc\td\né");
+        assert_eq!(sanitize_with(input, Sanitize::Escape),       "a^[[31mb^@^?M-^[1mc\td\né");
+        assert_eq!(sanitize_with("\r\u{85}", Sanitize::Escape),  "^MM-^E");
+    }
+
+    /// A reader that returns its bytes a few at a time, to split control codes across reads.
+    #[cfg(feature = "std")]
+    struct Trickle<'a>(&'a [u8], usize);
+
+    #[cfg(feature = "std")]
+    impl std::io::Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let n = self.1.min(self.0.len()).min(buf.len());
+            buf[..n].copy_from_slice(&self.0[..n]);
+            self.0 = &self.0[n..];
+            Ok(n)
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn strip_reader_matches_strip_ansi() {
+        use std::io::Read;
+
+        let inputs = [
+            format!("{BOLD}{RED}error!{RESET} done"),
+            format!("a{}b{RESET}é\n{}", truecolor(1, 2, 3), hyperlink("https://example.com", "link")),
+            "\x1b7saved\x1b8 \x1b[2\nx".to_string(),
+            "broken \x1b[2".to_string(),
+            "é\x1bé".to_string(),
+            "a\x1b\x1b[31mb".to_string(),
+        ];
+
+        for input in inputs {
+            for size in [1, 2, 3, 7, 4096] {
+                let mut out = String::new();
+                StripAnsiReader::new(Trickle(input.as_bytes(), size)).read_to_string(&mut out).unwrap();
+                assert_eq!(out, strip_ansi(&input), "{input:?} in chunks of {size}");
+            }
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn strip_reader_lines() {
+        use std::io::BufRead;
+
+        let input = format!("{GREEN}one{RESET}\ntwo {BOLD}2{RESET}\n");
+        let lines: Vec<String> = StripAnsiReader::new(Trickle(input.as_bytes(), 5)).lines().map(Result::unwrap).collect();
+        assert_eq!(lines, ["one", "two 2"]);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn truncate_keeps_styling() {
+        let red = format!("{RED}abcdef{RESET}");
+        assert_eq!(truncate_visible(&red, 3),   format!("{RED}abc{RESET}"));
+        assert_eq!(truncate_visible(&red, 6),   red);
+        assert_eq!(truncate_visible(&red, 0),   "");
+        assert_eq!(truncate_visible("abcdef", 4), "abcd");
+
+        let mixed = format!("{BOLD}ab{RESET}cd{GREEN}éf{RESET}");
+        assert_eq!(truncate_visible(&mixed, 2), format!("{BOLD}ab{RESET}"));
+        assert_eq!(truncate_visible(&mixed, 3), format!("{BOLD}ab{RESET}c"));
+        assert_eq!(truncate_visible(&mixed, 5), format!("{BOLD}ab{RESET}cd{GREEN}é{RESET}"));
+
+        let undone = format!("{BOLD}ab{NORMAL_INTENSITY}{RED}cd{DEFAULT}ef");
+        assert_eq!(truncate_visible(&undone, 3), format!("{BOLD}ab{NORMAL_INTENSITY}{RED}c{RESET}"));
+        assert_eq!(truncate_visible(&undone, 5), format!("{BOLD}ab{NORMAL_INTENSITY}{RED}cd{DEFAULT}e"));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn truncate_with_ellipsis() {
+        let text = format!("{ITALIC}abcdef{RESET}");
+        assert_eq!(truncate_visible_with(&text, 4, "…"),     format!("{ITALIC}abc…{RESET}"));
+        assert_eq!(truncate_visible_with(&text, 4, "..."),   format!("{ITALIC}a...{RESET}"));
+        assert_eq!(truncate_visible_with(&text, 2, "..."),   "..");
+        assert_eq!(truncate_visible_with(&text, 6, "…"),     text);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn wrap_plain_text() {
+        assert_eq!(wrap_visible("the quick brown fox", 10),    ["the quick", "brown fox"]);
+        assert_eq!(wrap_visible("the quick brown fox", 9),     ["the quick", "brown fox"]);
+        assert_eq!(wrap_visible("a  b   c", 4),                ["a  b", "c"]);
+        assert_eq!(wrap_visible("  indented text", 10),        ["  indented", "text"]);
+        assert_eq!(wrap_visible("one\n\ntwo three", 5),        ["one", "", "two", "three"]);
+        assert_eq!(wrap_visible("abcdefgh ij", 3),             ["abc", "def", "gh", "ij"]);
+        assert_eq!(wrap_visible("ab cdefgh", 4),               ["ab", "cdef", "gh"]);
+        assert_eq!(wrap_visible("ab", 0),                      ["a", "b"]);
+        assert_eq!(wrap_visible("", 5),                        [] as [&str; 0]);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn wrap_carries_styles_across_lines() {
+        let text = format!("{BOLD}aaa bbb{RESET} ccc {GREEN}dddddd");
+        assert_eq!(wrap_visible(&text, 3), [
+            format!("{BOLD}aaa{RESET}"),
+            format!("{BOLD}bbb{RESET}"),
+            "ccc".to_string(),
+            format!("{GREEN}ddd{RESET}"),
+            format!("{GREEN}ddd{RESET}"),
+        ]);
+
+        let lines = wrap_visible(&format!("x {UNDERLINE}y{}z", hyperlink("url", "w")), 10);
+        assert_eq!(lines, [format!("x {UNDERLINE}ywz{RESET}")]);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn pad_ignores_codes() {
+        let cell = format!("{BOLD}{RED}abc{RESET}");
+        assert_eq!(pad_visible(&cell, 5, Align::Left),    format!("{cell}  "));
+        assert_eq!(pad_visible(&cell, 5, Align::Right),   format!("  {cell}"));
+        assert_eq!(pad_visible(&cell, 6, Align::Center),  format!(" {cell}  "));
+        assert_eq!(pad_visible(&cell, 3, Align::Center),  cell);
+        assert_eq!(pad_visible(&cell, 1, Align::Right),   cell);
+        assert_eq!(pad_visible("é", 3, Align::Center),    " é ");
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn ansi_string_tracks_width() {
+        let mut s = AnsiString::new();
+        assert_eq!(s.width(), 0);
+
+        s.push_styled(RED, "héllo");
+        s.push_plain(" ");
+        s.push_plain(&format!("{BOLD}x{RESET}"));
+        s.push_styled(Style::new().italic(), "");
+
+        assert_eq!(s.width(), visible_width(s.as_str()));
+        assert_eq!(s.width(), 7);
+        assert_eq!(s.to_string(), format!("{RED}héllo{RESET} {BOLD}x{RESET}\x1b[3m{RESET}"));
+    }
+}
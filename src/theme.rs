@@ -0,0 +1,163 @@
+// Themes: the colors an application uses, in one place.
+
+#[cfg(feature = "alloc")]
+use alloc::string::{String, ToString};
+
+use crate::{Color, NamedColor, Style};
+#[cfg(feature = "alloc")]
+use crate::Stylize;
+
+/// The colors an application uses for its different kinds of text, so that they can be defined in
+/// one place and the whole theme can be swapped by changing one value. Start from `Palette::dark()`
+/// or `Palette::light()` and change slots with the builder methods, then use the `_fg` methods to
+/// get a style for each kind of text:
+///
+/// ```
+/// use flower_pot::*;
+/// # #[cfg(feature = "alloc")] {
+///
+/// let theme = Palette::dark().error(Color::Palette(196));
+///
+/// println!("{}", "saved".styled(theme.success_fg()));
+/// println!("{}", "disk full".styled(theme.error_fg()));
+/// assert_eq!(theme.error_fg().to_string(), color_256(196));
+/// # }
+/// ```
+///
+/// The default palette is `Palette::dark()`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Palette {
+    primary:    Color,
+    secondary:  Color,
+    error:      Color,
+    warning:    Color,
+    success:    Color,
+}
+
+impl Palette {
+    /// A palette for terminals with a dark background, using the bright named colors: bright blue
+    /// (primary), bright cyan (secondary), bright red (error), bright yellow (warning), and bright
+    /// green (success).
+    pub const fn dark() -> Palette {
+        Palette {
+            primary:    Color::Named(NamedColor::BrightBlue),
+            secondary:  Color::Named(NamedColor::BrightCyan),
+            error:      Color::Named(NamedColor::BrightRed),
+            warning:    Color::Named(NamedColor::BrightYellow),
+            success:    Color::Named(NamedColor::BrightGreen),
+        }
+    }
+
+    /// A palette for terminals with a light background, using the standard named colors: blue
+    /// (primary), magenta (secondary), red (error), and green (success). Warnings use palette color
+    /// #130, a dark orange, because yellow is hard to read on a light background.
+    pub const fn light() -> Palette {
+        Palette {
+            primary:    Color::Named(NamedColor::Blue),
+            secondary:  Color::Named(NamedColor::Magenta),
+            error:      Color::Named(NamedColor::Red),
+            warning:    Color::Palette(130),
+            success:    Color::Named(NamedColor::Green),
+        }
+    }
+
+    /// Set the primary color.
+    pub const fn primary(mut self, color: Color) -> Palette { self.primary = color; self }
+    /// Set the secondary color.
+    pub const fn secondary(mut self, color: Color) -> Palette { self.secondary = color; self }
+    /// Set the color for errors.
+    pub const fn error(mut self, color: Color) -> Palette { self.error = color; self }
+    /// Set the color for warnings.
+    pub const fn warning(mut self, color: Color) -> Palette { self.warning = color; self }
+    /// Set the color for successes.
+    pub const fn success(mut self, color: Color) -> Palette { self.success = color; self }
+
+    /// A style with the primary color as its foreground color.
+    pub const fn primary_fg(&self) -> Style { Style::new().fg(self.primary) }
+    /// A style with the secondary color as its foreground color.
+    pub const fn secondary_fg(&self) -> Style { Style::new().fg(self.secondary) }
+    /// A style with the color for errors as its foreground color.
+    pub const fn error_fg(&self) -> Style { Style::new().fg(self.error) }
+    /// A style with the color for warnings as its foreground color.
+    pub const fn warning_fg(&self) -> Style { Style::new().fg(self.warning) }
+    /// A style with the color for successes as its foreground color.
+    pub const fn success_fg(&self) -> Style { Style::new().fg(self.success) }
+}
+
+impl Default for Palette {
+    fn default() -> Palette {
+        Palette::dark()
+    }
+}
+
+/// A label for log messages and other CLI output: `text` in square brackets, in bold and in
+/// `color`, followed by `RESET`. The label is styled the same way `styled` would style it, so
+/// while styling is turned off (see `set_colors_enabled` and `set_attributes_enabled`), only the
+/// parts that are still on are kept, and the label can end up as just `[text]`.
+///
+/// ```
+/// use flower_pot::*;
+///
+/// let label = label("BUILD", Color::Named(NamedColor::Magenta));
+/// assert_eq!(label, "\x1b[1;35m[BUILD]\x1b[0m");
+/// eprintln!("{label} compiling 3 files");
+/// ```
+#[cfg(feature = "alloc")]
+pub fn label(text: &str, color: Color) -> String {
+    format_args!("[{text}]").styled(Style::new().bold().fg(color)).to_string()
+}
+
+/// The label `[ERROR]` in bold red, which is `"\x1b[1;31m[ERROR]\x1b[0m"` (see `label`).
+#[cfg(feature = "alloc")]
+pub fn error_label() -> String {
+    label("ERROR", Color::Named(NamedColor::Red))
+}
+
+/// The label `[WARN]` in bold yellow, which is `"\x1b[1;33m[WARN]\x1b[0m"` (see `label`).
+#[cfg(feature = "alloc")]
+pub fn warn_label() -> String {
+    label("WARN", Color::Named(NamedColor::Yellow))
+}
+
+/// The label `[INFO]` in bold blue, which is `"\x1b[1;34m[INFO]\x1b[0m"` (see `label`).
+#[cfg(feature = "alloc")]
+pub fn info_label() -> String {
+    label("INFO", Color::Named(NamedColor::Blue))
+}
+
+/// The label `[SUCCESS]` in bold green, which is `"\x1b[1;32m[SUCCESS]\x1b[0m"` (see `label`).
+#[cfg(feature = "alloc")]
+pub fn success_label() -> String {
+    label("SUCCESS", Color::Named(NamedColor::Green))
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use crate::*;
+
+    #[test]
+    fn palette_slots() {
+        let dark = Palette::default();
+        assert_eq!(dark, Palette::dark());
+        assert_eq!(dark.primary_fg().to_string(),   BRIGHT_BLUE);
+        assert_eq!(dark.secondary_fg().to_string(), BRIGHT_CYAN);
+        assert_eq!(dark.error_fg().to_string(),     BRIGHT_RED);
+        assert_eq!(dark.warning_fg().to_string(),   BRIGHT_YELLOW);
+        assert_eq!(dark.success_fg().to_string(),   BRIGHT_GREEN);
+
+        let light = Palette::light().success(Color::Rgb(0, 128, 0));
+        assert_eq!(light.warning_fg().to_string(), color_256(130));
+        assert_eq!(light.success_fg().to_string(), truecolor(0, 128, 0));
+        assert_eq!(light.error_fg().to_string(),   RED);
+    }
+
+    #[test]
+    fn level_labels() {
+        assert_eq!(error_label(),   "\x1b[1;31m[ERROR]\x1b[0m");
+        assert_eq!(warn_label(),    "\x1b[1;33m[WARN]\x1b[0m");
+        assert_eq!(info_label(),    "\x1b[1;34m[INFO]\x1b[0m");
+        assert_eq!(success_label(), "\x1b[1;32m[SUCCESS]\x1b[0m");
+        assert_eq!(label("", Color::Palette(9)), "\x1b[1;38;5;9m[]\x1b[0m");
+    }
+}
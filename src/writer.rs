@@ -0,0 +1,262 @@
+// Writers that reset their style when they go out of scope.
+
+use core::fmt;
+
+use crate::RESET;
+#[cfg(feature = "std")]
+use crate::{Style, Styled};
+
+/// A guard that writes a style when it is created and `RESET` when it is dropped, so that the
+/// style is reset even if the code using it returns early. Text written through the guard (it
+/// implements `core::fmt::Write`) is passed on to the underlying writer unchanged.
+///
+/// ```
+/// use core::fmt::Write;
+/// use flower_pot::*;
+///
+/// fn report(out: &mut String, ok: bool) -> Result<(), core::fmt::Error> {
+///     let mut w = StyleWriter::new(out, BOLD)?;
+///     write!(w, "status: ")?;
+///     if !ok {
+///         return Err(core::fmt::Error); // the RESET is still written
+///     }
+///     write!(w, "ok")
+/// }
+///
+/// let mut out = String::new();
+/// let _ = report(&mut out, false);
+/// assert_eq!(out, format!("{BOLD}status: {RESET}"));
+/// ```
+///
+/// Guards can be nested by creating one guard on top of another, in which case the inner guard is
+/// dropped first. Because `RESET` clears every style, the outer guard's style is no longer in
+/// effect after the inner guard is dropped, even though the outer guard is still alive. If you
+/// need the outer style again, write it through the outer guard after the inner one is gone.
+pub struct StyleWriter<'a, W: fmt::Write + ?Sized> {
+    inner:      &'a mut W,
+    finished:   bool,
+}
+
+impl<'a, W: fmt::Write + ?Sized> StyleWriter<'a, W> {
+    /// Write `style` into `inner` and return a guard that writes `RESET` into `inner` when dropped.
+    pub fn new(inner: &'a mut W, style: impl fmt::Display) -> Result<StyleWriter<'a, W>, fmt::Error> {
+        write!(inner, "{style}")?;
+        Ok(StyleWriter { inner, finished: false })
+    }
+
+    /// Write `RESET` now instead of when the guard is dropped, returning any error that occurs
+    /// (errors that occur while dropping the guard are ignored).
+    pub fn finish(mut self) -> fmt::Result {
+        self.finished = true;
+        self.inner.write_str(RESET)
+    }
+}
+
+impl<W: fmt::Write + ?Sized> fmt::Write for StyleWriter<'_, W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.inner.write_str(s)
+    }
+}
+
+impl<W: fmt::Write + ?Sized> Drop for StyleWriter<'_, W> {
+    fn drop(&mut self) {
+        if !self.finished {
+            let _ = self.inner.write_str(RESET);
+        }
+    }
+}
+
+/// The same as `StyleWriter`, but for writers that implement `std::io::Write` (such as stdout or
+/// a file) rather than `core::fmt::Write`. Requires the `std` feature.
+///
+/// ```
+/// use std::io::Write;
+/// use flower_pot::*;
+///
+/// let mut stdout = std::io::stdout();
+/// let mut w = IoStyleWriter::new(&mut stdout, RED).unwrap();
+/// writeln!(w, "this is red").unwrap();
+/// drop(w);
+/// println!("and this isn't");
+/// ```
+#[cfg(feature = "std")]
+pub struct IoStyleWriter<'a, W: std::io::Write + ?Sized> {
+    inner:      &'a mut W,
+    finished:   bool,
+}
+
+#[cfg(feature = "std")]
+impl<'a, W: std::io::Write + ?Sized> IoStyleWriter<'a, W> {
+    /// Write `style` into `inner` and return a guard that writes `RESET` into `inner` when dropped.
+    pub fn new(inner: &'a mut W, style: impl fmt::Display) -> std::io::Result<IoStyleWriter<'a, W>> {
+        write!(inner, "{style}")?;
+        Ok(IoStyleWriter { inner, finished: false })
+    }
+
+    /// Write `RESET` now instead of when the guard is dropped, returning any error that occurs
+    /// (errors that occur while dropping the guard are ignored).
+    pub fn finish(mut self) -> std::io::Result<()> {
+        self.finished = true;
+        self.inner.write_all(RESET.as_bytes())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<W: std::io::Write + ?Sized> std::io::Write for IoStyleWriter<'_, W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.inner.write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<W: std::io::Write + ?Sized> Drop for IoStyleWriter<'_, W> {
+    fn drop(&mut self) {
+        if !self.finished {
+            let _ = self.inner.write_all(RESET.as_bytes());
+        }
+    }
+}
+
+/// Write `text` wrapped in `style` and `RESET` to a writer that implements `std::io::Write`,
+/// without building a String first. This writes exactly what `Styled` displays as (so only the
+/// text is written while styling is turned off; see `set_colors_enabled`), and doesn't flush
+/// `w`. Requires the `std` feature.
+///
+/// ```
+/// use flower_pot::*;
+///
+/// let mut stdout = std::io::stdout().lock();
+/// write_styled(&mut stdout, &Style::new().bold(), "done").unwrap();
+/// ```
+#[cfg(feature = "std")]
+pub fn write_styled(w: &mut impl std::io::Write, style: &Style, text: &str) -> std::io::Result<()> {
+    write!(w, "{}", Styled::new(text, style))
+}
+
+/// Print `code` to stdout, run `body`, and then print `RESET`, for imperative code that prints a
+/// styled block with several `print!` calls. The `RESET` is printed even if `body` returns early
+/// or panics (it's printed by a guard while the panic unwinds, though not if panics abort), so the
+/// style can't leak into the rest of the output. Returns whatever `body` returns. Errors writing
+/// to stdout are ignored. Requires the `std` feature; see `with_reset_to` for other writers.
+///
+/// ```
+/// use flower_pot::*;
+///
+/// with_reset(RED, || {
+///     print!("error: ");
+///     println!("something went wrong");
+/// });
+/// ```
+#[cfg(feature = "std")]
+pub fn with_reset<R>(code: &str, body: impl FnOnce() -> R) -> R {
+    let mut stdout = std::io::stdout();
+    let _guard = IoStyleWriter::new(&mut stdout, code).ok();
+    body()
+}
+
+/// Like `with_reset`, but writes to `w` instead of stdout, and passes `w` to `body` to write to.
+/// An error is returned if writing `code` fails, in which case `body` isn't run. Errors writing
+/// the `RESET` are ignored, like when an `IoStyleWriter` is dropped. Requires the `std` feature.
+///
+/// ```
+/// use std::io::Write;
+/// use flower_pot::*;
+///
+/// let mut out = Vec::new();
+/// with_reset_to(&mut out, BOLD, |w| write!(w, "loud")).unwrap().unwrap();
+/// assert_eq!(out, format!("{BOLD}loud{RESET}").into_bytes());
+/// ```
+#[cfg(feature = "std")]
+pub fn with_reset_to<W: std::io::Write + ?Sized, R>(w: &mut W, code: &str, body: impl FnOnce(&mut W) -> R) -> std::io::Result<R> {
+    let guard = IoStyleWriter::new(w, code)?;
+    Ok(body(&mut *guard.inner))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::*;
+    use core::fmt::Write as _;
+    #[cfg(feature = "std")]
+    use std::io::Write as _;
+
+    #[test]
+    fn style_writer_resets_on_drop() {
+        let mut out = String::new();
+        {
+            let mut w = StyleWriter::new(&mut out, GREEN).unwrap();
+            w.write_str("ok").unwrap();
+        }
+        assert_eq!(out, format!("{GREEN}ok{RESET}"));
+    }
+
+    #[test]
+    fn style_writer_nested() {
+        let mut out = String::new();
+        {
+            let mut outer = StyleWriter::new(&mut out, BOLD).unwrap();
+            write!(outer, "a").unwrap();
+            {
+                let mut inner = StyleWriter::new(&mut outer, RED).unwrap();
+                write!(inner, "b").unwrap();
+            }
+            write!(outer, "c").unwrap();
+        }
+        assert_eq!(out, format!("{BOLD}a{RED}b{RESET}c{RESET}"));
+    }
+
+    #[test]
+    fn style_writer_finish() {
+        let mut out = String::new();
+        let w = StyleWriter::new(&mut out, DIM).unwrap();
+        w.finish().unwrap();
+        assert_eq!(out, format!("{DIM}{RESET}"));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn io_style_writer_resets_on_drop() {
+        let mut out: Vec<u8> = Vec::new();
+        {
+            let mut w = IoStyleWriter::new(&mut out, Style::new().italic()).unwrap();
+            w.write_all(b"text").unwrap();
+        }
+        assert_eq!(out, format!("{ITALIC}text{RESET}").into_bytes());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn with_reset_to_writer() {
+        let mut out: Vec<u8> = Vec::new();
+        let n = with_reset_to(&mut out, GREEN, |w| {
+            write!(w, "a").unwrap();
+            write!(w, "b").unwrap();
+            2
+        });
+        assert_eq!(n.unwrap(), 2);
+        assert_eq!(out, format!("{GREEN}ab{RESET}").into_bytes());
+
+        let mut out: Vec<u8> = Vec::new();
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            with_reset_to(&mut out, RED, |w| {
+                write!(w, "oops").unwrap();
+                panic!("body panicked");
+            })
+        }));
+        assert!(result.is_err());
+        assert_eq!(out, format!("{RED}oops{RESET}").into_bytes());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn write_styled_to_bytes() {
+        let mut out: Vec<u8> = Vec::new();
+        write_styled(&mut out, &Style::new().bold().fg(Color::Named(NamedColor::Red)), "a").unwrap();
+        write_styled(&mut out, &Style::new(), "b").unwrap();
+        assert_eq!(out, format!("\x1b[1;31ma{RESET}b").into_bytes());
+    }
+}
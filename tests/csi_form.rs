@@ -0,0 +1,24 @@
+// This test changes the global CSI form, which affects the output of every gated emitter, so it
+// lives in its own test binary to avoid interfering with other tests.
+
+#![cfg(feature = "alloc")]
+
+use flower_pot::*;
+
+#[test]
+fn gated_emitters_follow_the_csi_form() {
+    let style = Style::new().bold().fg(Color::Named(NamedColor::Green));
+    assert_eq!(csi_form(), CsiForm::TwoByte);
+    assert_eq!("ok".styled(style).to_string(), format!("\x1b[1;32mok{RESET}"));
+
+    set_csi_form(CsiForm::SingleByte);
+    assert_eq!(csi_form(), CsiForm::SingleByte);
+    assert_eq!("ok".styled(style).to_string(), "\u{9b}1;32mok\u{9b}0m");
+    assert_eq!(style!(bold, red; "x"), "\u{9b}1m\u{9b}31mx\u{9b}0m");
+    assert_eq!("a\x1b[".styled("\x1b7\x1b").to_string(), "\x1b7\x1ba\x1b[\u{9b}0m");
+    assert_eq!(boxed(&format!("{RED}x"), &Style::new()), "┌─┐\n│\x1b[31mx\u{9b}0m│\n└─┘");
+    assert_eq!(GREEN, "\x1b[32m");
+
+    set_csi_form(CsiForm::TwoByte);
+    assert_eq!("ok".styled(style).to_string(), format!("\x1b[1;32mok{RESET}"));
+}
@@ -0,0 +1,160 @@
+// These tests change global switches, so they live in their own test binary to avoid interfering
+// with other tests. Each switch is only changed by a single test function.
+
+#![cfg(feature = "alloc")]
+
+use flower_pot::*;
+
+#[test]
+fn gated_emitters_follow_the_switches() {
+    let style = Style::new().bold().fg(Color::Named(NamedColor::Green));
+    assert!(colors_enabled() && attributes_enabled());
+    assert_eq!("ok".styled(GREEN).to_string(), format!("{GREEN}ok{RESET}"));
+    assert_eq!("ok".styled(style).to_string(), format!("\x1b[1;32mok{RESET}"));
+    assert_eq!(style!(bold; "{}", 1), format!("{BOLD}1{RESET}"));
+    assert_eq!(attr(ITALIC), ITALIC);
+
+    set_colors_enabled(false);
+    assert!(!colors_enabled());
+    assert_eq!("ok".styled(GREEN).to_string(), "ok");
+    assert_eq!("ok".styled(style).to_string(), format!("{BOLD}ok{RESET}"));
+    assert_eq!("ok".styled(format!("{RESET}{BLUE_BG}")).to_string(), format!("{RESET}ok{RESET}"));
+    assert_eq!("x".styled("\x1b[38;5m").to_string(), "x");
+    assert_eq!("x".styled("\x1b[38:5:1m").to_string(), "x");
+    assert_eq!("x".styled(format!("{BOLD}\x1b[38;5m")).to_string(), format!("{BOLD}x{RESET}"));
+    assert_eq!(style!(bold, fg(256: 3); "{}", 1), format!("{BOLD}1{RESET}"));
+    assert_eq!(style!(fg(256: 3); "{}", 1), "1");
+    assert_eq!(fg(Color::Palette(3)), "");
+    assert_eq!(bg(Color::Named(NamedColor::Red)), "");
+    assert_eq!(GREEN, "\x1b[32m");
+
+    set_attributes_enabled(false);
+    assert!(!attributes_enabled());
+    assert_eq!("ok".styled(style).to_string(), "ok");
+    assert_eq!(style!(bold, fg(256: 3); "{}", 1), "1");
+    assert_eq!(attr(ITALIC), "");
+    assert_eq!(dim_fg(Color::Palette(196)), "");
+
+    set_colors_enabled(true);
+    assert_eq!(dim_fg(Color::Palette(196)),                  color_256(88));
+    assert_eq!(dim_fg(Color::Named(NamedColor::BrightRed)),  RED);
+    assert_eq!(dim_fg(Color::Rgb(100, 200, 50)),             truecolor(60, 120, 30));
+
+    assert_eq!("ok".styled(style).to_string(), format!("{GREEN}ok{RESET}"));
+    assert_eq!("x".styled("\x1b[38:5:1m").to_string(), "x");
+    assert_eq!(style!(italic, red, bright_white_bg; "x"), format!("{RED}{BRIGHT_WHITE_BG}x{RESET}"));
+    assert_eq!(format!("{}x", hyperlink("u", "t").styled(ITALIC)), format!("{}x", hyperlink("u", "t")));
+
+    set_attributes_enabled(true);
+    assert_eq!(fg(Color::Palette(3)), color_256(3));
+    assert_eq!(dim_fg(Color::Palette(196)), "\x1b[2;38;5;196m");
+    assert_eq!("ok".styled(GREEN).to_string(), format!("{GREEN}ok{RESET}"));
+
+    {
+        let _outer = suppress_colors();
+        assert!(!colors_enabled() && !attributes_enabled());
+        assert_eq!("ok".styled(style).to_string(), "ok");
+        {
+            let _inner = suppress_colors();
+            assert_eq!(style!(bold; "{}", 1), "1");
+        }
+        assert!(!colors_enabled() && !attributes_enabled());
+    }
+    assert!(colors_enabled() && attributes_enabled());
+
+    set_attributes_enabled(false);
+    drop(suppress_colors());
+    assert!(colors_enabled() && !attributes_enabled());
+    set_attributes_enabled(true);
+
+    // Readers on other threads only ever see the output for one setting or the other.
+    let styled = format!("\x1b[1;32mok{RESET}");
+    let bold = format!("{BOLD}ok{RESET}");
+    std::thread::scope(|scope| {
+        let readers: Vec<_> = (0..4).map(|_| scope.spawn(|| {
+            for _ in 0..1000 {
+                let out = "ok".styled(style).to_string();
+                assert!(out == styled || out == bold, "{out:?}");
+            }
+        })).collect();
+
+        for i in 0..1000 {
+            set_colors_enabled(i % 2 == 1);
+        }
+        for reader in readers {
+            reader.join().unwrap();
+        }
+    });
+    assert!(colors_enabled());
+}
+
+#[test]
+fn bright_colors_fall_back_on_legacy_terminals() {
+    assert!(!legacy_bright());
+    for &color in NamedColor::all() {
+        assert_eq!(bright_bg_adaptive(color), color.bg());
+        assert_eq!(bright_bg_256_adaptive(color), color.bg());
+        assert_eq!(bright_fg_adaptive(color), color.fg());
+    }
+
+    set_legacy_bright(true);
+    #[allow(deprecated)]
+    let old_name = legacy_bright_bg();
+    assert!(old_name);
+    assert_eq!(bright_bg_adaptive(NamedColor::BrightBlack),   BLACK_BG);
+    assert_eq!(bright_bg_adaptive(NamedColor::BrightYellow),  YELLOW_BG);
+    assert_eq!(bright_bg_adaptive(NamedColor::BrightWhite),   WHITE_BG);
+    assert_eq!(bright_bg_adaptive(NamedColor::Cyan),          CYAN_BG);
+    assert_eq!(bright_bg_256_adaptive(NamedColor::BrightBlack),   color_256_bg(8));
+    assert_eq!(bright_bg_256_adaptive(NamedColor::Cyan),          CYAN_BG);
+    for &color in NamedColor::all() {
+        let index = bright_fallback(color);
+        if index < 8 {
+            assert_eq!(bright_fg_adaptive(color), color.fg());
+        } else {
+            assert_eq!(bright_fg_adaptive(color), color_256(index));
+        }
+    }
+
+    set_legacy_bright(false);
+    assert_eq!(bright_bg_adaptive(NamedColor::BrightWhite),   BRIGHT_WHITE_BG);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn supports_256_color_can_be_overridden() {
+    set_supports_256_color(true);
+    assert!(supports_256_color());
+
+    set_supports_256_color(false);
+    assert!(!supports_256_color());
+
+    // The last value set wins, whatever other threads read in the meantime.
+    std::thread::scope(|scope| {
+        for _ in 0..4 {
+            scope.spawn(|| {
+                for _ in 0..1000 {
+                    supports_256_color();
+                }
+            });
+        }
+        for i in 0..1000 {
+            set_supports_256_color(i % 2 == 0);
+        }
+    });
+    assert!(!supports_256_color());
+}
+
+#[test]
+fn adaptive_colors_follow_the_background() {
+    let (dark, light) = (Color::Named(NamedColor::White), Color::Palette(16));
+    assert_eq!(background(), Background::Dark);
+    assert_eq!(adaptive(dark, light), dark);
+
+    set_background(Background::Light);
+    assert_eq!(background(), Background::Light);
+    assert_eq!(adaptive(dark, light), light);
+
+    set_background(Background::Dark);
+    assert_eq!(adaptive(dark, light), dark);
+}